@@ -0,0 +1,25 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use wilson::FP;
+
+fn lower_bounds(c: &mut Criterion) {
+    let data: Vec<(FP, FP)> = (1..=1000).map(|i| ((i % 37) as FP, (i % 37 + i % 11 + i) as FP)).collect();
+    c.bench_function("wilson_lower_bound_1000", |b| {
+        b.iter(|| {
+            black_box(&data)
+                .iter()
+                .map(|&(s, n)| wilson::wilson_lower_bound(s, n, 1.96))
+                .sum::<FP>()
+        })
+    });
+    c.bench_function("wilson_lower_fast_1000", |b| {
+        b.iter(|| {
+            black_box(&data)
+                .iter()
+                .map(|&(s, n)| wilson::wilson_lower_fast(s, n, 1.96))
+                .sum::<FP>()
+        })
+    });
+}
+
+criterion_group!(benches, lower_bounds);
+criterion_main!(benches);
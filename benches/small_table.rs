@@ -0,0 +1,29 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn small_counts(c: &mut Criterion) {
+    c.bench_function("wilson_lower_bound_small", |b| {
+        b.iter(|| {
+            let mut acc = 0.0;
+            for n in 1..=32u8 {
+                for s in 0..=n {
+                    acc += wilson::wilson_lower_bound_small(black_box(s), n);
+                }
+            }
+            acc
+        })
+    });
+    c.bench_function("wilson_lower_bound_computed", |b| {
+        b.iter(|| {
+            let mut acc = 0.0;
+            for n in 1..=32u8 {
+                for s in 0..=n {
+                    acc += wilson::wilson_lower_bound(black_box(s.into()), n.into(), 1.96);
+                }
+            }
+            acc
+        })
+    });
+}
+
+criterion_group!(benches, small_counts);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use wilson::FP;
+
+fn scalar(c: &mut Criterion) {
+    c.bench_function("wilson", |b| {
+        b.iter(|| wilson::wilson(black_box(12.0), black_box(40.0), black_box(1.96)))
+    });
+}
+
+fn batch(c: &mut Criterion) {
+    let data: Vec<(FP, FP)> = (1..=1000).map(|i| ((i % 37) as FP, (i % 37 + i % 11) as FP)).collect();
+    c.bench_function("try_wilson_batch_1000", |b| {
+        b.iter(|| wilson::try_wilson_batch(black_box(&data), black_box(1.96)))
+    });
+}
+
+fn fixed_z(c: &mut Criterion) {
+    // The same z reused for many (successes, trials) pairs
+    let z = wilson::z_from_confidence(0.95);
+    c.bench_function("wilson_grid_fixed_z", |b| {
+        b.iter(|| {
+            let mut acc = 0.0;
+            for n in 1..=32 {
+                for s in 0..=n {
+                    acc += wilson::wilson(black_box(s as FP), n as FP, z).low;
+                }
+            }
+            acc
+        })
+    });
+}
+
+criterion_group!(benches, scalar, batch, fixed_z);
+criterion_main!(benches);
@@ -0,0 +1,265 @@
+//! Bayesian comparisons of proportions using Beta posteriors.
+
+use crate::gamma::ln_beta;
+use crate::{score_statistic, WilsonResult, FP};
+
+/// Steps of the numerical integration grid covering each posterior.
+const GRID_STEPS: usize = 16384;
+
+/// Beyond this many standard deviations from the mean a posterior is treated as having no mass.
+const SPAN_SD: FP = 12.0;
+
+/// Probability that a draw from `Beta(alpha_b, beta_b)` exceeds a draw from `Beta(alpha_a, beta_a)`,
+/// for integer `alpha_b`, as a sum of beta functions.
+fn beats_closed_form(alpha_a: FP, beta_a: FP, alpha_b: FP, beta_b: FP) -> FP {
+    if alpha_b > alpha_a {
+        // The sum runs over `alpha_b` terms, so sum over the smaller parameter of the complementary event.
+        return 1.0 - beats_closed_form(alpha_b, beta_b, alpha_a, beta_a);
+    }
+    let norm = ln_beta(alpha_a, beta_a);
+    let terms = alpha_b as u64;
+    (0..terms)
+        .map(|i| {
+            let i = i as FP;
+            (ln_beta(alpha_a + i, beta_a + beta_b) - (beta_b + i).ln() - ln_beta(1.0 + i, beta_b) - norm).exp()
+        })
+        .sum()
+}
+
+/// Grid of `GRID_STEPS + 1` points covering almost all mass of `Beta(alpha, beta)`, with densities at them.
+fn beta_grid(alpha: FP, beta: FP) -> (Vec<FP>, Vec<FP>) {
+    let total = alpha + beta;
+    let mean = alpha / total;
+    let sd = (alpha * beta / (total * total * (total + 1.0))).sqrt();
+    let lo = (mean - SPAN_SD * sd).max(0.0);
+    let hi = (mean + SPAN_SD * sd).min(1.0);
+    let h = (hi - lo) / GRID_STEPS as FP;
+    let norm = ln_beta(alpha, beta);
+    let xs: Vec<FP> = (0..=GRID_STEPS).map(|i| lo + h * i as FP).collect();
+    let density = xs
+        .iter()
+        .map(|&x| {
+            // Exponents of zero are skipped, so that `0 · ln 0` does not turn into NaN at the ends.
+            let mut log = -norm;
+            if alpha != 1.0 {
+                log += (alpha - 1.0) * x.ln();
+            }
+            if beta != 1.0 {
+                log += (beta - 1.0) * (1.0 - x).ln();
+            }
+            log.exp()
+        })
+        .collect();
+    (xs, density)
+}
+
+/// Same as [`beats_closed_form`] for any positive parameters: `∫ f_B(x) · F_A(x) dx` by the trapezoidal rule,
+/// with each posterior sampled on its own grid so that a narrow one next to a wide one is still resolved.
+fn beats_numerical(alpha_a: FP, beta_a: FP, alpha_b: FP, beta_b: FP) -> FP {
+    let (xs_a, density_a) = beta_grid(alpha_a, beta_a);
+    let mut cdf_a = Vec::with_capacity(xs_a.len());
+    let mut acc = 0.0;
+    cdf_a.push(0.0);
+    for i in 1..xs_a.len() {
+        acc += 0.5 * (density_a[i - 1] + density_a[i]) * (xs_a[i] - xs_a[i - 1]);
+        cdf_a.push(acc);
+    }
+    // Renormalize away the small error of integrating the density
+    let cdf_at = |x: FP| -> FP {
+        let (first, last) = (xs_a[0], xs_a[GRID_STEPS]);
+        if x <= first {
+            return 0.0;
+        }
+        if x >= last {
+            return 1.0;
+        }
+        let pos = (x - first) / (last - first) * GRID_STEPS as FP;
+        let i = (pos as usize).min(GRID_STEPS - 1);
+        let t = pos - i as FP;
+        (cdf_a[i] + t * (cdf_a[i + 1] - cdf_a[i])) / acc
+    };
+
+    let (xs_b, density_b) = beta_grid(alpha_b, beta_b);
+    let weighted: Vec<FP> = xs_b.iter().zip(&density_b).map(|(&x, &d)| d * cdf_at(x)).collect();
+    let mut integral = 0.0;
+    let mut mass = 0.0;
+    for i in 1..xs_b.len() {
+        let h = xs_b[i] - xs_b[i - 1];
+        integral += 0.5 * (weighted[i - 1] + weighted[i]) * h;
+        mass += 0.5 * (density_b[i - 1] + density_b[i]) * h;
+    }
+    integral / mass
+}
+
+/// Probability that arm B has a higher true rate than arm A, given their observed counts.
+///
+/// Uses Beta posteriors `Beta(s + 1, n − s + 1)` of both arms under flat (uniform) priors.
+/// For integer counts the exact closed-form sum of beta functions is evaluated in log-space,
+/// taking time proportional to the smaller number of successes.
+/// Fractional counts are handled by numerical integration with an absolute error of about `1e-6`.
+///
+/// With the `f32` feature, rounding limits the accuracy to about `1e-4`,
+/// and log-gamma cancellation to about `1e-3` for counts in the thousands.
+///
+/// Identical arms give exactly `0.5`.
+/// Returns NaN for negative or non-finite counts and for successes exceeding trials.
+///
+/// ```
+/// let p = wilson::prob_b_beats_a(10.0, 100.0, 20.0, 100.0);
+/// assert!(p > 0.97 && p < 0.98);
+/// assert!((wilson::prob_b_beats_a(5.0, 50.0, 5.0, 50.0) - 0.5).abs() < 1e-9);
+/// ```
+#[must_use]
+pub fn prob_b_beats_a(s_a: FP, n_a: FP, s_b: FP, n_b: FP) -> FP {
+    let valid = |s: FP, n: FP| s.is_finite() && n.is_finite() && s >= 0.0 && s <= n;
+    if !valid(s_a, n_a) || !valid(s_b, n_b) {
+        return FP::NAN;
+    }
+    let (alpha_a, beta_a) = (s_a + 1.0, n_a - s_a + 1.0);
+    let (alpha_b, beta_b) = (s_b + 1.0, n_b - s_b + 1.0);
+    if alpha_a == alpha_b && beta_a == beta_b {
+        return 0.5;
+    }
+    let p = if [s_a, n_a, s_b, n_b].iter().all(|x| x.fract() == 0.0) {
+        beats_closed_form(alpha_a, beta_a, alpha_b, beta_b)
+    } else {
+        beats_numerical(alpha_a, beta_a, alpha_b, beta_b)
+    };
+    p.clamp(0.0, 1.0)
+}
+
+impl WilsonResult {
+    /// Approximate Bayes factor of `p ≠ p0` against `p = p0` for `successes` out of `trials`,
+    /// the counts this interval was computed from. Above 1 the data favors a rate different from `p0`.
+    ///
+    /// Built from the same [score statistic](crate::score_statistic) `z = (s/n − p0) / sqrt(p0·(1 − p0) / n)`
+    /// that the Wilson interval inverts (`p0` is inside `wilson(s, n, z)` exactly when the statistic is within `±z`),
+    /// so the factor depends only on the counts and `p0`, not on the level of the interval.
+    /// Uses the BIC (unit-information prior) approximation `exp(z² / 2) / √n`: under the alternative,
+    /// the prior on the rate is a normal centered on `p0` carrying as much information as one trial.
+    /// The approximation is accurate for large `trials`; with a handful of trials treat it as a rough guide.
+    ///
+    /// Returns 1 (no evidence either way) without trials, and NaN unless `0 < p0 < 1`.
+    ///
+    /// ```
+    /// let counts = (90.0, 100.0);
+    /// let result = wilson::wilson(counts.0, counts.1, 1.96);
+    /// assert!(result.bayes_factor_vs(counts.0, counts.1, 0.5) > 1e10);
+    /// // Data right at the null favor it, by a factor of √n
+    /// assert!((wilson::wilson(50.0, 100.0, 1.96).bayes_factor_vs(50.0, 100.0, 0.5) - 0.1).abs() < 1e-6);
+    /// ```
+    #[must_use]
+    pub fn bayes_factor_vs(&self, successes: FP, trials: FP, p0: FP) -> FP {
+        if !(p0 > 0.0 && p0 < 1.0) {
+            return FP::NAN;
+        }
+        if trials <= 0.0 {
+            return 1.0;
+        }
+        let z = score_statistic(successes, trials, p0);
+        (0.5 * (z * z - trials.ln())).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::tol;
+
+    #[test]
+    fn closed_form() {
+        // Both posteriors are uniform
+        assert_abs_diff_eq!(prob_b_beats_a(0.0, 0.0, 0.0, 0.0), 0.5, epsilon = tol(1e-12));
+        // Beta(2, 1) against uniform: ∫ 2x · x dx = 2/3
+        assert_abs_diff_eq!(prob_b_beats_a(0.0, 0.0, 1.0, 1.0), 2.0 / 3.0, epsilon = tol(1e-12));
+        for (s, n) in [(0.0, 10.0), (3.0, 10.0), (50.0, 1000.0), (700.0, 700.0)] {
+            assert_abs_diff_eq!(prob_b_beats_a(s, n, s, n), 0.5, epsilon = tol(1e-9));
+        }
+        let p = prob_b_beats_a(12.0, 200.0, 2000.0, 20000.0);
+        assert!(p > 0.9 && p < 1.0);
+        assert!(prob_b_beats_a(3.0, 2.0, 1.0, 1.0).is_nan());
+        assert!(prob_b_beats_a(1.0, 2.0, -1.0, 1.0).is_nan());
+    }
+
+    #[test]
+    fn symmetry() {
+        let mut arms = vec![(0.0, 5.0), (3.0, 10.0), (4.5, 10.0), (30.0, 99.5), (7.0, 7.0)];
+        if cfg!(feature = "f64") {
+            arms.push((1000.0, 5000.0));
+        }
+        let tolerance = if cfg!(feature = "f64") { 1e-6 } else { 1e-4 };
+        for &(sa, na) in &arms {
+            for &(sb, nb) in &arms {
+                let sum = prob_b_beats_a(sa, na, sb, nb) + prob_b_beats_a(sb, nb, sa, na);
+                assert_abs_diff_eq!(sum, 1.0, epsilon = tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn numerical_matches_closed_form() {
+        let mut cases = vec![
+            (3.0, 10.0, 5.0, 10.0),
+            (0.0, 4.0, 1.0, 2.0),
+            (40.0, 100.0, 52.0, 100.0),
+            (100.0, 100.0, 99.0, 100.0),
+        ];
+        if cfg!(feature = "f64") {
+            cases.push((5.0, 20.0, 3000.0, 10000.0));
+        }
+        let tolerance = if cfg!(feature = "f64") { 1e-6 } else { 1e-4 };
+        for (sa, na, sb, nb) in cases {
+            let (aa, ba, ab, bb) = (sa + 1.0, na - sa + 1.0, sb + 1.0, nb - sb + 1.0);
+            assert_abs_diff_eq!(beats_numerical(aa, ba, ab, bb), beats_closed_form(aa, ba, ab, bb), epsilon = tolerance);
+        }
+        let between = prob_b_beats_a(3.0, 10.0, 5.5, 10.0);
+        assert!(between > prob_b_beats_a(3.0, 10.0, 5.0, 10.0));
+        assert!(between < prob_b_beats_a(3.0, 10.0, 6.0, 10.0));
+    }
+
+    #[test]
+    fn bayes_factor() {
+        let factor = |s: FP, n: FP, p0: FP| crate::wilson(s, n, 1.96).bayes_factor_vs(s, n, p0);
+        assert!(factor(90.0, 100.0, 0.5) > 1e10);
+        assert!(factor(0.0, 1000.0, 0.2) > 1e10);
+        assert!(factor(200.0, 1000.0, 0.2) < 0.05);
+
+        // Borderline: the score statistic squared equals ln n
+        let (n, p0): (FP, FP) = (400.0, 0.3);
+        let s = n * p0 + (n.ln() * n * p0 * (1.0 - p0)).sqrt();
+        assert_abs_diff_eq!(factor(s, n, p0), 1.0, epsilon = tol(1e-9) * 100.0);
+        assert!(factor(s + 5.0, n, p0) > 1.0);
+        assert!(factor(s - 5.0, n, p0) < 1.0);
+
+        // Symmetric around the null and monotone in the deviation
+        assert_abs_diff_eq!(factor(60.0, 100.0, 0.5), factor(40.0, 100.0, 0.5), epsilon = tol(1e-9));
+        assert!(factor(65.0, 100.0, 0.5) > factor(60.0, 100.0, 0.5));
+
+        assert_eq!(factor(0.0, 0.0, 0.5), 1.0);
+        assert!(factor(3.0, 10.0, 0.0).is_nan());
+        assert!(factor(3.0, 10.0, 1.0).is_nan());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn monte_carlo() {
+        use rand::rngs::StdRng;
+        use rand::{RngExt, SeedableRng};
+
+        // Beta(s + 1, n − s + 1) is the distribution of the (s + 1)-th smallest of n + 1 uniforms
+        fn draw(s: usize, n: usize, rng: &mut StdRng) -> FP {
+            let mut u: Vec<FP> = (0..=n).map(|_| rng.random_range(0.0..1.0)).collect();
+            u.sort_by(|a, b| a.total_cmp(b));
+            u[s]
+        }
+        let mut rng = StdRng::seed_from_u64(5);
+        let draws = 40_000;
+        let wins = (0..draws)
+            .filter(|_| draw(12, 40, &mut rng) > draw(8, 35, &mut rng))
+            .count();
+        let estimate = wins as FP / draws as FP;
+        assert_abs_diff_eq!(prob_b_beats_a(8.0, 35.0, 12.0, 40.0), estimate, epsilon = 0.01);
+    }
+}
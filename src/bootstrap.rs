@@ -0,0 +1,263 @@
+//! Resampling intervals: percentile bootstrap and jackknife.
+
+#[cfg(feature = "rand")]
+use rand::{Rng, RngExt};
+
+use crate::{WilsonResult, FP};
+
+/// Take `low` and `high` percentiles of resampled proportions for the given two-sided confidence level.
+///
+/// `confidence` outside `[0, 1]` is clamped, and no proportions at all result in the `[0, 1]` interval.
+fn percentile_interval(mut proportions: Vec<FP>, confidence: FP) -> WilsonResult {
+    if proportions.is_empty() {
+        return WilsonResult { low: 0.0, high: 1.0 };
+    }
+    proportions.sort_by(|a, b| a.total_cmp(b));
+    let last = (proportions.len() - 1) as FP;
+    let tail = 0.5 * (1.0 - confidence.clamp(0.0, 1.0));
+    let at = |q: FP| proportions[(q * last).round() as usize];
+    WilsonResult {
+        low: at(tail),
+        high: at(1.0 - tail),
+    }
+}
+
+/// SplitMix64, enough for resampling without depending on `rand`.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut x = self.0;
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^ (x >> 31)
+    }
+}
+
+/// Percentile bootstrap interval for `successes` out of `trials`, for comparison with [`wilson`](crate::wilson).
+///
+/// Each of `n_bootstrap` resamples consists of `trials` Bernoulli draws with the observed rate,
+/// and the interval is taken from the percentiles of the resampled proportions (percentile method).
+/// Uses a small internal generator seeded by `seed`, so the result is reproducible and does not need the `rand` feature.
+/// Time is proportional to `trials · n_bootstrap`.
+///
+/// Zero `trials` or `n_bootstrap` result in the `[0, 1]` interval. `confidence` outside `[0, 1]` is clamped.
+/// Unlike the Wilson interval, the bootstrap one collapses to a point when no or all trials succeeded.
+///
+/// ```
+/// let boot = wilson::wilson_boot_ci(30, 100, 0.95, 2000, 1);
+/// let wilson = wilson::wilson_confidence(30.0, 100.0, 0.95);
+/// assert!((boot.low - wilson.low).abs() < 0.02);
+/// assert!((boot.high - wilson.high).abs() < 0.02);
+/// ```
+#[must_use]
+pub fn wilson_boot_ci(successes: u64, trials: u64, confidence: FP, n_bootstrap: usize, seed: u64) -> WilsonResult {
+    if trials == 0 || n_bootstrap == 0 {
+        return WilsonResult { low: 0.0, high: 1.0 };
+    }
+    let successes = successes.min(trials);
+    // A draw is a success when the random number is below `rate · 2⁶⁴`, exactly.
+    let threshold = (u128::from(successes) << 64) / u128::from(trials);
+    let mut rng = SplitMix64(seed);
+    let proportions = (0..n_bootstrap)
+        .map(|_| {
+            let hits = (0..trials).filter(|_| u128::from(rng.next()) < threshold).count();
+            hits as FP / trials as FP
+        })
+        .collect();
+    percentile_interval(proportions, confidence)
+}
+
+/// Jackknife interval `p̂ ± z·SE_jk` for `successes` out of `trials`, for diagnostic comparison with [`wilson`](crate::wilson).
+///
+/// The jackknife standard error `SE_jk = sqrt((n−1)/n · Σ(p̂₍ᵢ₎ − p̂)²)` over leave-one-out estimates `p̂₍ᵢ₎`
+/// has a closed form for binomial data, `sqrt(p̂(1−p̂) / (n−1))`, so this is O(1).
+/// It is the delta-method (Wald) standard error with `n − 1` instead of `n`,
+/// and shares its weaknesses: the interval collapses to a point when no or all trials succeeded.
+///
+/// Bounds are clamped to `[0, 1]`. Fewer than two trials result in the `[0, 1]` interval.
+///
+/// ```
+/// let jk = wilson::wilson_jackknife(50, 100, 1.96);
+/// assert!((jk.low - 0.4015).abs() < 1e-4);
+/// assert!((jk.high - 0.5985).abs() < 1e-4);
+/// ```
+#[must_use]
+pub fn wilson_jackknife(successes: u64, trials: u64, z: FP) -> WilsonResult {
+    if trials < 2 {
+        return WilsonResult { low: 0.0, high: 1.0 };
+    }
+    let n = trials as FP;
+    let p = successes.min(trials) as FP / n;
+    let se = (p * (1.0 - p) / (n - 1.0)).sqrt();
+    WilsonResult {
+        low: (p - z * se).clamp(0.0, 1.0),
+        high: (p + z * se).clamp(0.0, 1.0),
+    }
+}
+
+/// Percentile bootstrap interval for the proportion of `true` outcomes.
+///
+/// Outcomes are resampled with replacement `resamples` times and the interval is
+/// taken from the percentiles of the resampled proportions.
+/// Memory usage is proportional to `resamples`, not to the number of outcomes times resamples.
+///
+/// Useful as a brute-force cross-check of [`wilson`](crate::wilson) on messy data.
+/// Empty `outcomes` or zero `resamples` result in the `[0, 1]` interval. `confidence` outside `[0, 1]` is clamped.
+#[cfg(feature = "rand")]
+pub fn bootstrap_interval(outcomes: &[bool], confidence: FP, resamples: u32, rng: &mut impl Rng) -> WilsonResult {
+    if outcomes.is_empty() || resamples == 0 {
+        return WilsonResult { low: 0.0, high: 1.0 };
+    }
+    let n = outcomes.len();
+    let proportions = (0..resamples)
+        .map(|_| {
+            let successes = (0..n).filter(|_| outcomes[rng.random_range(0..n)]).count();
+            successes as FP / n as FP
+        })
+        .collect();
+    percentile_interval(proportions, confidence)
+}
+
+/// Like [`bootstrap_interval`], but for `(outcome, weight)` pairs.
+///
+/// Pairs are resampled uniformly and each resample contributes its weighted proportion
+/// `Σ weight·outcome / Σ weight`. Resamples with zero total weight count as proportion 0.
+#[cfg(feature = "rand")]
+pub fn bootstrap_interval_weighted(
+    outcomes: &[(bool, FP)],
+    confidence: FP,
+    resamples: u32,
+    rng: &mut impl Rng,
+) -> WilsonResult {
+    if outcomes.is_empty() || resamples == 0 {
+        return WilsonResult { low: 0.0, high: 1.0 };
+    }
+    let n = outcomes.len();
+    let proportions = (0..resamples)
+        .map(|_| {
+            let (mut successes, mut total) = (0.0, 0.0);
+            for _ in 0..n {
+                let (outcome, weight) = outcomes[rng.random_range(0..n)];
+                total += weight;
+                if outcome {
+                    successes += weight;
+                }
+            }
+            if total > 0.0 {
+                successes / total
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    percentile_interval(proportions, confidence)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+    #[cfg(feature = "rand")]
+    use rand::rngs::StdRng;
+    #[cfg(feature = "rand")]
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::tol;
+    use crate::{wilson, wilson_confidence};
+
+    #[test]
+    fn boot_ci_overlaps_wilson() {
+        for (s, n) in [(1, 10), (5, 10), (8, 10), (20, 200), (150, 500), (999, 1000)] {
+            let boot = wilson_boot_ci(s, n, 0.95, 1000, 7);
+            let reference = wilson_confidence(s as FP, n as FP, 0.95);
+            assert!(boot.low <= reference.high && reference.low <= boot.high);
+            assert!(boot.low <= boot.high);
+        }
+        let boot = wilson_boot_ci(400, 1000, 0.95, 2000, 3);
+        let reference = wilson_confidence(400.0, 1000.0, 0.95);
+        assert_abs_diff_eq!(boot.low, reference.low, epsilon = 0.01);
+        assert_abs_diff_eq!(boot.high, reference.high, epsilon = 0.01);
+    }
+
+    #[test]
+    fn jackknife() {
+        for n in [50u64, 100, 1000] {
+            let jk = wilson_jackknife(n / 2, n, 1.96);
+            let reference = wilson(n as FP / 2.0, n as FP, 1.96);
+            assert_abs_diff_eq!(jk.low, reference.low, epsilon = 0.02);
+            assert_abs_diff_eq!(jk.high, reference.high, epsilon = 0.02);
+            assert_abs_diff_eq!(jk.midpoint(), 0.5, epsilon = tol(1e-6));
+        }
+
+        // Leave-one-out estimates computed directly
+        let (s, n) = (7u64, 30u64);
+        let p = s as FP / n as FP;
+        let loo = |x: u64| (s - x) as FP / (n - 1) as FP;
+        let sum = s as FP * (loo(1) - p).powi(2) + (n - s) as FP * (loo(0) - p).powi(2);
+        let se = ((n - 1) as FP / n as FP * sum).sqrt();
+        assert_abs_diff_eq!(wilson_jackknife(s, n, 2.0).high, p + 2.0 * se, epsilon = tol(1e-6));
+
+        assert_eq!(wilson_jackknife(0, 50, 1.96), WilsonResult { low: 0.0, high: 0.0 });
+        assert_eq!(wilson_jackknife(50, 50, 1.96), WilsonResult { low: 1.0, high: 1.0 });
+        assert_eq!(wilson_jackknife(1, 1, 1.96), WilsonResult { low: 0.0, high: 1.0 });
+    }
+
+    #[test]
+    fn boot_ci_reproducible() {
+        assert_eq!(wilson_boot_ci(7, 40, 0.9, 500, 11), wilson_boot_ci(7, 40, 0.9, 500, 11));
+        assert_ne!(wilson_boot_ci(70, 400, 0.9, 500, 11), wilson_boot_ci(70, 400, 0.9, 500, 12));
+        assert_eq!(wilson_boot_ci(0, 0, 0.95, 100, 1), WilsonResult { low: 0.0, high: 1.0 });
+        assert_eq!(wilson_boot_ci(5, 5, 0.95, 100, 1), WilsonResult { low: 1.0, high: 1.0 });
+        assert_eq!(wilson_boot_ci(0, 5, 0.95, 100, 1), WilsonResult { low: 0.0, high: 0.0 });
+    }
+
+    #[test]
+    fn percentiles_out_of_range() {
+        let proportions: Vec<FP> = (0..=10).map(|i| i as FP / 10.0).collect();
+        let full = WilsonResult { low: 0.0, high: 1.0 };
+        assert_eq!(percentile_interval(proportions.clone(), 1.5), full);
+        assert_eq!(percentile_interval(proportions.clone(), 1.0), full);
+        assert_eq!(percentile_interval(proportions.clone(), -0.5), WilsonResult { low: 0.5, high: 0.5 });
+        assert_eq!(percentile_interval(Vec::new(), 0.95), full);
+        let out = wilson_boot_ci(30, 100, 2.0, 100, 1);
+        assert!(out.low <= out.high);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn close_to_wilson() {
+        let outcomes: Vec<bool> = (0..500).map(|i| i % 10 < 3).collect();
+        let mut rng = StdRng::seed_from_u64(42);
+        let out = bootstrap_interval(&outcomes, 0.95, 2000, &mut rng);
+        let reference = wilson_confidence(150.0, 500.0, 0.95);
+        assert_abs_diff_eq!(out.low, reference.low, epsilon = 0.01);
+        assert_abs_diff_eq!(out.high, reference.high, epsilon = 0.01);
+
+        let weighted: Vec<(bool, FP)> = outcomes.iter().map(|&o| (o, 2.0)).collect();
+        let out = bootstrap_interval_weighted(&weighted, 0.95, 2000, &mut rng);
+        assert_abs_diff_eq!(out.low, reference.low, epsilon = 0.01);
+        assert_abs_diff_eq!(out.high, reference.high, epsilon = 0.01);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn weights_matter() {
+        // Successes carry three times the weight of failures, so the weighted rate is 0.75
+        let outcomes: Vec<(bool, FP)> = (0..400).map(|i| if i % 2 == 0 { (true, 3.0) } else { (false, 1.0) }).collect();
+        let mut rng = StdRng::seed_from_u64(1);
+        let out = bootstrap_interval_weighted(&outcomes, 0.95, 1000, &mut rng);
+        assert!(out.low < 0.75 && out.high > 0.75);
+        assert!(out.low > 0.6);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn degenerate() {
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(bootstrap_interval(&[], 0.95, 100, &mut rng), WilsonResult { low: 0.0, high: 1.0 });
+        assert_eq!(bootstrap_interval(&[true], 0.95, 0, &mut rng), WilsonResult { low: 0.0, high: 1.0 });
+        assert_eq!(bootstrap_interval(&[true, true], 0.95, 10, &mut rng), WilsonResult { low: 1.0, high: 1.0 });
+    }
+}
@@ -0,0 +1,91 @@
+//! Detecting a shift of the underlying rate in a series of counts.
+
+use crate::compare::proportion_difference_interval;
+use crate::{wilson, WilsonResult, FP};
+
+/// Split of a series found by [`detect_change`].
+#[derive(Debug, PartialEq)]
+pub struct ChangePoint {
+    /// Index of the first element after the change
+    pub index: usize,
+    /// Interval for the pooled rate of `series[..index]`
+    pub before: WilsonResult,
+    /// Interval for the pooled rate of `series[index..]`
+    pub after: WilsonResult,
+}
+
+/// Find where the rate of a series of `(successes, trials)` (e.g. daily counts) most clearly shifted.
+///
+/// Every split point is scanned once, pooling the counts on both sides with prefix sums, so the scan is `O(n)`.
+/// A split is significant if the difference interval of the two pooled rates ([`proportion_difference_interval`])
+/// excludes zero at the given `z`, and the split with the interval furthest from zero is reported.
+/// Returns `None` if no split is significant or the series has fewer than two elements.
+///
+/// This is a single step of binary segmentation: to find further changes, call it again on both halves.
+/// As all splits are tried, a larger `z` than for a single comparison avoids reporting noise as changes.
+///
+/// ```
+/// let series = [(10.0, 100.0), (12.0, 100.0), (9.0, 100.0), (30.0, 100.0), (28.0, 100.0)];
+/// let change = wilson::detect_change(&series, 3.0).unwrap();
+/// assert_eq!(change.index, 3);
+/// assert!(change.before.high < change.after.low);
+/// ```
+#[must_use]
+pub fn detect_change(series: &[(FP, FP)], z: FP) -> Option<ChangePoint> {
+    let (total_s, total_n) = series.iter().fold((0.0, 0.0), |(s, n), &(ds, dn)| (s + ds, n + dn));
+    // (index, separation, successes and trials before the index)
+    let mut best: Option<(usize, FP, FP, FP)> = None;
+    let (mut s, mut n) = (0.0, 0.0);
+    for index in 1..series.len() {
+        s += series[index - 1].0;
+        n += series[index - 1].1;
+        if n <= 0.0 || total_n - n <= 0.0 {
+            continue;
+        }
+        let difference = proportion_difference_interval(total_s - s, total_n - n, s, n, z);
+        let separation = difference.low.max(-difference.high);
+        if separation > 0.0 && best.is_none_or(|(_, b, _, _)| separation > b) {
+            best = Some((index, separation, s, n));
+        }
+    }
+    let (index, _, s, n) = best?;
+    Some(ChangePoint {
+        index,
+        before: wilson(s, n, z),
+        after: wilson(total_s - s, total_n - n, z),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ops::RangeBounds;
+
+    use super::*;
+
+    #[test]
+    fn known_jump() {
+        // Deterministic wobble around 5% for 30 days, then around 8%
+        let series: Vec<(FP, FP)> = (0..60)
+            .map(|i| {
+                let base = if i < 30 { 50.0 } else { 80.0 };
+                (base + [-4.0, 3.0, 0.0, 5.0, -3.0][i % 5], 1000.0)
+            })
+            .collect();
+        let change = detect_change(&series, 3.0).unwrap();
+        assert_eq!(change.index, 30);
+        assert!(change.before.contains(&0.05));
+        assert!(change.after.contains(&0.08));
+
+        let falling: Vec<(FP, FP)> = series.iter().rev().copied().collect();
+        assert_eq!(detect_change(&falling, 3.0).unwrap().index, 30);
+    }
+
+    #[test]
+    fn no_change() {
+        let flat: Vec<(FP, FP)> = (0..60).map(|i| (50.0 + [-4.0, 3.0, 0.0, 5.0, -3.0][i % 5], 1000.0)).collect();
+        assert_eq!(detect_change(&flat, 3.0), None);
+        assert_eq!(detect_change(&[], 2.0), None);
+        assert_eq!(detect_change(&[(5.0, 10.0)], 2.0), None);
+        assert_eq!(detect_change(&[(0.0, 0.0), (5.0, 10.0), (0.0, 0.0)], 2.0), None);
+    }
+}
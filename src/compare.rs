@@ -0,0 +1,636 @@
+//! Comparing proportions between groups.
+
+use crate::gamma::chi_square_sf;
+use crate::normal::normal_cdf;
+use crate::{
+    confidence_from_z, try_wilson, wilson, wilson_confidence, wilson_parts, z_from_confidence, WilsonError, WilsonParts,
+    WilsonResult, FP,
+};
+
+/// Interval for the difference of two proportions `s1/n1 − s2/n2`,
+/// combined from the two Wilson intervals (Newcombe's hybrid score method).
+///
+/// Unlike other results of this crate, bounds are within `[-1, 1]`.
+/// If `low > 0`, the first proportion is likely higher; if `high < 0`, the second one is.
+#[must_use]
+pub fn proportion_difference_interval(s1: FP, n1: FP, s2: FP, n2: FP, z: FP) -> WilsonResult {
+    let p1 = if n1 > 0.0 { s1 / n1 } else { 0.5 };
+    let p2 = if n2 > 0.0 { s2 / n2 } else { 0.5 };
+    let w1 = wilson(s1, n1, z);
+    let w2 = wilson(s2, n2, z);
+    let d = p1 - p2;
+    let low = d - ((p1 - w1.low).powi(2) + (w2.high - p2).powi(2)).sqrt();
+    let high = d + ((w1.high - p1).powi(2) + (p2 - w2.low).powi(2)).sqrt();
+    WilsonResult { low, high }
+}
+
+/// Sign of a difference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sign {
+    /// Greater than zero
+    Positive,
+    /// Less than zero
+    Negative,
+    /// Exactly zero
+    Zero,
+}
+
+/// A/B readout returned by [`diff_summary`], in percentage points.
+#[derive(Debug, PartialEq)]
+pub struct DiffSummary {
+    /// Observed difference `s1/n1 − s2/n2` in percentage points, NaN if either group has zero trials
+    pub difference: FP,
+    /// [`proportion_difference_interval`] in percentage points, within `[-100, 100]`
+    pub interval: WilsonResult,
+    /// Whether the interval excludes zero, i.e. the difference is significant at the requested level
+    pub significant: bool,
+    /// Sign of the observed difference, whether significant or not
+    pub direction: Sign,
+}
+
+/// Difference of two proportions `s1/n1 − s2/n2` with its interval at the two-sided `confidence` level,
+/// significance and direction, in percentage points.
+///
+/// Zero `trials` in either group leave nothing to compare: `difference` is NaN, `direction` is [`Sign::Zero`]
+/// and the result is not significant.
+///
+/// ```
+/// use wilson::{diff_summary, Sign};
+/// // Newcombe (1998), 56/70 against 48/80
+/// let out = diff_summary(56.0, 70.0, 48.0, 80.0, 0.95);
+/// assert!((out.difference - 20.0).abs() < 1e-4);
+/// assert!((out.interval.low - 5.24).abs() < 0.01 && (out.interval.high - 33.39).abs() < 0.01);
+/// assert!(out.significant);
+/// assert_eq!(out.direction, Sign::Positive);
+/// ```
+#[must_use]
+pub fn diff_summary(s1: FP, n1: FP, s2: FP, n2: FP, confidence: FP) -> DiffSummary {
+    let p1 = if n1 > 0.0 { s1 / n1 } else { FP::NAN };
+    let p2 = if n2 > 0.0 { s2 / n2 } else { FP::NAN };
+    let difference = 100.0 * (p1 - p2);
+    let interval = proportion_difference_interval(s1, n1, s2, n2, z_from_confidence(confidence));
+    let direction = if difference > 0.0 {
+        Sign::Positive
+    } else if difference < 0.0 {
+        Sign::Negative
+    } else {
+        Sign::Zero
+    };
+    DiffSummary {
+        difference,
+        interval: WilsonResult {
+            low: 100.0 * interval.low,
+            high: 100.0 * interval.high,
+        },
+        significant: interval.low > 0.0 || interval.high < 0.0,
+        direction,
+    }
+}
+
+/// Interval for the odds ratio `(s1 / (n1 − s1)) / (s2 / (n2 − s2))` of two groups (Woolf's logit method).
+///
+/// If any of the four counts is zero, 0.5 is added to each of them (Haldane–Anscombe correction).
+/// Unlike other results of this crate, bounds are within `[0, ∞)`.
+#[must_use]
+pub fn odds_ratio_interval(s1: FP, n1: FP, s2: FP, n2: FP, z: FP) -> WilsonResult {
+    let (mut a, mut b, mut c, mut d) = (s1, n1 - s1, s2, n2 - s2);
+    if a == 0.0 || b == 0.0 || c == 0.0 || d == 0.0 {
+        a += 0.5;
+        b += 0.5;
+        c += 0.5;
+        d += 0.5;
+    }
+    let log_or = (a * d / (b * c)).ln();
+    let se = (1.0 / a + 1.0 / b + 1.0 / c + 1.0 / d).sqrt();
+    WilsonResult {
+        low: (log_or - z * se).exp(),
+        high: (log_or + z * se).exp(),
+    }
+}
+
+/// Interval for the highest true proportion among several `(successes, trials)` groups.
+///
+/// Simply taking the interval of the group with the best observed rate is optimistic,
+/// because that group was selected for being lucky (winner's curse).
+/// Instead, Wilson intervals of all groups are calculated at Šidák-adjusted confidence `confidence^(1/k)`,
+/// so they hold simultaneously with probability at least `confidence`,
+/// and the maximum true rate then lies between the largest lower bound and the largest upper bound.
+///
+/// The result is conservative, getting wider as more groups are compared.
+/// With a single group it is the plain [`wilson_confidence`] interval; without groups it is `[0, 1]`.
+///
+/// ```
+/// let groups = [(48.0, 400.0), (55.0, 400.0), (61.0, 400.0)];
+/// let best = wilson::wilson_max(&groups, 0.95);
+/// let naive = wilson::wilson_confidence(61.0, 400.0, 0.95);
+/// assert!(best.low < naive.low && best.high > naive.high);
+/// ```
+#[must_use]
+pub fn wilson_max(data: &[(FP, FP)], confidence: FP) -> WilsonResult {
+    if data.is_empty() {
+        return WilsonResult { low: 0.0, high: 1.0 };
+    }
+    wilson_joint_intervals(data, confidence)
+        .into_iter()
+        .fold(WilsonResult { low: 0.0, high: 0.0 }, |acc, w| WilsonResult {
+            low: acc.low.max(w.low),
+            high: acc.high.max(w.high),
+        })
+}
+
+/// Wilson intervals of independent `(successes, trials)` groups that all hold simultaneously
+/// with probability `confidence`.
+///
+/// Each interval is calculated at the Šidák-adjusted confidence `confidence^(1/k)` for `k` groups,
+/// which is exact for independent groups, so the intervals get wider as more groups are added.
+/// With a single group this is the plain [`wilson_confidence`] interval.
+///
+/// `confidence` is deliberately the joint level for all groups together (Šidák semantics),
+/// not an `individual_confidence` for each item: passing a per-item level would make the intervals too wide.
+/// For intervals at a given per-item level, call [`wilson_confidence`] on each group instead.
+///
+/// ```
+/// let groups = [(48.0, 400.0), (55.0, 400.0), (61.0, 400.0)];
+/// let joint = wilson::wilson_joint_intervals(&groups, 0.95);
+/// let single = wilson::wilson_confidence(55.0, 400.0, 0.95);
+/// assert!(joint[1].low < single.low && joint[1].high > single.high);
+/// ```
+#[must_use]
+pub fn wilson_joint_intervals(items: &[(FP, FP)], confidence: FP) -> Vec<WilsonResult> {
+    let adjusted = confidence.powf(1.0 / items.len() as FP);
+    items.iter().map(|&(s, n)| wilson_confidence(s, n, adjusted)).collect()
+}
+
+/// Wilson interval for one of `k` independent items with the same `(successes, trials)`, widened so that
+/// the interval of the `rank`-th worst covered item still has the coverage of `z`.
+///
+/// For fleet monitoring: with `rank = 1`, the lower bound holds for the worst of `k` services at once,
+/// using the Šidák-adjusted level `confidence^(1/k)` as in [`wilson_joint_intervals`].
+/// A higher `rank` tolerates `rank − 1` missed intervals, so the per-item level solves
+/// `P(Binomial(k, 1 − level) < rank) = confidence`, and the interval gets narrower again.
+/// With `k = 1` this is plain [`wilson`].
+///
+/// Panics unless `1 ≤ rank ≤ k`.
+///
+/// ```
+/// let fleet = wilson::wilson_ranked_tail(990.0, 1000.0, 1.96, 50, 1);
+/// let single = wilson::wilson(990.0, 1000.0, 1.96);
+/// assert!(fleet.low < single.low);
+/// assert!(wilson::wilson_ranked_tail(990.0, 1000.0, 1.96, 50, 3).low > fleet.low);
+/// ```
+#[must_use]
+pub fn wilson_ranked_tail(successes: FP, trials: FP, z: FP, k: usize, rank: usize) -> WilsonResult {
+    assert!((1..=k).contains(&rank), "rank must be within 1..=k");
+    if k == 1 {
+        return wilson(successes, trials, z);
+    }
+    let confidence = confidence_from_z(z);
+    let level = if rank == 1 {
+        confidence.powf(1.0 / k as FP)
+    } else {
+        // Probability that fewer than `rank` intervals miss, increasing in `level`
+        let coverage = |level: FP| {
+            let odds = (1.0 - level) / level;
+            let mut term = level.powi(k as i32);
+            let mut sum = term;
+            for j in 0..rank - 1 {
+                term *= (k - j) as FP / (j + 1) as FP * odds;
+                sum += term;
+            }
+            sum
+        };
+        let (mut low, mut high): (FP, FP) = (0.0, 1.0);
+        for _ in 0..100 {
+            let mid = 0.5 * (low + high);
+            if mid <= low || mid >= high {
+                break;
+            }
+            if coverage(mid) < confidence {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        high
+    };
+    wilson(successes, trials, z_from_confidence(level))
+}
+
+/// Approximate probability that the true rate behind `a` is higher than the one behind `b`:
+/// `Φ((center_a − center_b) / sqrt(se_a² + se_b²))`, with standard errors `half_width / z`.
+fn probability_of_improvement(a: &WilsonParts, b: &WilsonParts, z: FP) -> FP {
+    let difference = a.center - b.center;
+    let spread = a.half_width.hypot(b.half_width);
+    if spread == 0.0 {
+        // No uncertainty at all, e.g. for `z = 0`
+        return if difference > 0.0 {
+            1.0
+        } else if difference < 0.0 {
+            0.0
+        } else {
+            0.5
+        };
+    }
+    normal_cdf(difference * z / spread)
+}
+
+/// Matrix of pairwise probabilities that item `i` is truly better than item `j` for `(successes, trials)` items,
+/// `result[i][j]`, for Condorcet-style ranking of leaderboards.
+///
+/// Each probability is a normal approximation from the Wilson centers and half-widths at `z`,
+/// treating `half_width / z` as standard errors. The diagonal is `0.5` and `result[j][i] = 1 − result[i][j]`.
+///
+/// ```
+/// let m = wilson::wilson_compare_all(&[(60.0, 100.0), (40.0, 100.0)], 1.96);
+/// assert!(m[0][1] > 0.99);
+/// assert_eq!(m[1][0], 1.0 - m[0][1]);
+/// ```
+#[must_use]
+pub fn wilson_compare_all(items: &[(FP, FP)], z: FP) -> Vec<Vec<FP>> {
+    let parts: Vec<WilsonParts> = items.iter().map(|&(s, n)| wilson_parts(s, n, z)).collect();
+    let k = items.len();
+    let mut result = vec![vec![0.5; k]; k];
+    for i in 0..k {
+        for j in i + 1..k {
+            let p = probability_of_improvement(&parts[i], &parts[j], z);
+            result[i][j] = p;
+            result[j][i] = 1.0 - p;
+        }
+    }
+    result
+}
+
+/// Item that is more likely than not better than every other one according to [`wilson_compare_all`],
+/// if there is such an item.
+///
+/// There is at most one. A single item wins by default, an empty list has no winner.
+#[must_use]
+pub fn wilson_condorcet_winner(items: &[(FP, FP)], z: FP) -> Option<usize> {
+    let matrix = wilson_compare_all(items, z);
+    (0..items.len()).find(|&i| (0..items.len()).all(|j| j == i || matrix[i][j] > 0.5))
+}
+
+/// Result of [`wilson_cohort_comparison`].
+#[derive(Debug, PartialEq)]
+pub struct CohortComparison {
+    /// Treatment minus control difference interval for each cohort, see [`proportion_difference_interval`]
+    pub per_cohort: Vec<WilsonResult>,
+    /// Mantel–Haenszel pooled risk difference interval across all cohorts
+    pub pooled: WilsonResult,
+    /// p-value of Cochran's Q test for the hypothesis that the effect is the same in all cohorts.
+    /// Small values mean the treatment effect differs between cohorts and the pooled result should be taken with caution.
+    pub homogeneity_p_value: FP,
+}
+
+/// Analyse A/B test split into cohorts (e.g. new, returning and power users).
+///
+/// Each cohort is `(s_treatment, n_treatment, s_control, n_control)`.
+///
+/// The pooled interval uses Mantel–Haenszel weights `n_t·n_c / (n_t + n_c)` with a Wald-type variance,
+/// so it is less reliable than per-cohort intervals for tiny cohorts.
+/// Cohorts without trials in one of the arms are ignored. Cohorts without variance (e.g. all or no successes
+/// in both arms) still count in the pooled estimate with their full weight, but are left out of the homogeneity test.
+#[must_use]
+pub fn wilson_cohort_comparison(cohorts: &[(FP, FP, FP, FP)], z: FP) -> CohortComparison {
+    let per_cohort = cohorts
+        .iter()
+        .map(|&(st, nt, sc, nc)| proportion_difference_interval(st, nt, sc, nc, z))
+        .collect();
+
+    // (difference, Mantel-Haenszel weight, variance) for informative cohorts
+    let strata: Vec<(FP, FP, FP)> = cohorts
+        .iter()
+        .filter(|&&(_, nt, _, nc)| nt > 0.0 && nc > 0.0)
+        .map(|&(st, nt, sc, nc)| {
+            let pt = st / nt;
+            let pc = sc / nc;
+            let variance = pt * (1.0 - pt) / nt + pc * (1.0 - pc) / nc;
+            (pt - pc, nt * nc / (nt + nc), variance)
+        })
+        .collect();
+
+    let total_weight: FP = strata.iter().map(|&(_, w, _)| w).sum();
+    if strata.is_empty() {
+        return CohortComparison {
+            per_cohort,
+            pooled: WilsonResult { low: -1.0, high: 1.0 },
+            homogeneity_p_value: 1.0,
+        };
+    }
+    let pooled_diff = strata.iter().map(|&(d, w, _)| w * d).sum::<FP>() / total_weight;
+    let pooled_var = strata.iter().map(|&(_, w, v)| w * w * v).sum::<FP>() / (total_weight * total_weight);
+    let margin = z * pooled_var.sqrt();
+
+    let varying = strata.iter().filter(|&&(_, _, v)| v > 0.0);
+    let q: FP = varying.clone().map(|&(d, _, v)| (d - pooled_diff).powi(2) / v).sum();
+    let df = varying.count().saturating_sub(1);
+    let homogeneity_p_value = if df == 0 { 1.0 } else { chi_square_sf(q, df as FP) };
+
+    CohortComparison {
+        per_cohort,
+        pooled: WilsonResult {
+            low: (pooled_diff - margin).max(-1.0),
+            high: (pooled_diff + margin).min(1.0),
+        },
+        homogeneity_p_value,
+    }
+}
+
+/// Statistic used by [`homogeneity_test`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestKind {
+    /// Pearson's chi-square, `Σ (observed − expected)² / expected`, as in R's `prop.test` without continuity correction
+    PearsonChiSquare,
+    /// G-test (likelihood ratio), `2 Σ observed · ln(observed / expected)`
+    GTest,
+}
+
+/// Result of [`homogeneity_test`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HomogeneityTest {
+    /// Value of the test statistic
+    pub statistic: FP,
+    /// Degrees of freedom, the number of groups with trials minus one
+    pub df: u32,
+    /// Probability of a statistic at least this large if all groups share the same rate
+    pub p_value: FP,
+}
+
+/// Test whether `k` groups of `(successes, trials)` have the same underlying rate,
+/// before looking at pairwise comparisons.
+///
+/// Expected counts come from pooling all groups under the null hypothesis;
+/// the statistic is compared to the chi-square distribution with `k − 1` degrees of freedom.
+/// Groups with zero trials carry no information and are dropped, reducing `df`.
+/// If the pooled rate is exactly `0` or `1`, all groups agree perfectly: the statistic is `0` and the p-value `1`.
+///
+/// Fails with [`WilsonError::TooFewGroups`] when fewer than two groups have trials,
+/// and with the [`try_wilson`] errors for invalid counts.
+///
+/// ```
+/// use wilson::{homogeneity_test, TestKind};
+/// let groups = [(83.0, 86.0), (90.0, 93.0), (129.0, 136.0), (70.0, 82.0)];
+/// let out = homogeneity_test(&groups, TestKind::PearsonChiSquare).unwrap();
+/// assert_eq!(out.df, 3);
+/// assert!((out.statistic - 12.6).abs() < 0.01);
+/// assert!(out.p_value < 0.01);
+/// ```
+pub fn homogeneity_test(groups: &[(FP, FP)], kind: TestKind) -> Result<HomogeneityTest, WilsonError> {
+    for &(s, n) in groups {
+        try_wilson(s, n, 0.0)?;
+    }
+    let groups: Vec<(FP, FP)> = groups.iter().copied().filter(|&(_, n)| n > 0.0).collect();
+    if groups.len() < 2 {
+        return Err(WilsonError::TooFewGroups);
+    }
+    let df = (groups.len() - 1) as u32;
+    let pooled = groups.iter().map(|&(s, _)| s).sum::<FP>() / groups.iter().map(|&(_, n)| n).sum::<FP>();
+    if pooled <= 0.0 || pooled >= 1.0 {
+        return Ok(HomogeneityTest {
+            statistic: 0.0,
+            df,
+            p_value: 1.0,
+        });
+    }
+    let statistic: FP = groups
+        .iter()
+        .map(|&(s, n)| {
+            let cells = [(s, n * pooled), (n - s, n * (1.0 - pooled))];
+            match kind {
+                TestKind::PearsonChiSquare => cells.iter().map(|&(o, e)| (o - e).powi(2) / e).sum::<FP>(),
+                // `0 · ln 0` is taken as 0
+                TestKind::GTest => cells
+                    .iter()
+                    .filter(|&&(o, _)| o > 0.0)
+                    .map(|&(o, e)| 2.0 * o * (o / e).ln())
+                    .sum(),
+            }
+        })
+        .sum();
+    // Rounding can push a perfect fit slightly below zero
+    let statistic = statistic.max(0.0);
+    Ok(HomogeneityTest {
+        statistic,
+        df,
+        p_value: chi_square_sf(statistic, df as FP),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::tol;
+
+    #[test]
+    fn difference() {
+        // Newcombe (1998), example (a): 56/70 vs 48/80, method 10 gives 0.0524 to 0.3339
+        let out = proportion_difference_interval(56.0, 70.0, 48.0, 80.0, 1.959964);
+        assert_abs_diff_eq!(out.low, 0.0524, epsilon = 0.0001);
+        assert_abs_diff_eq!(out.high, 0.3339, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn odds_ratio() {
+        let out = odds_ratio_interval(20.0, 100.0, 10.0, 100.0, 1.96);
+        assert_abs_diff_eq!(out.low, 0.99428, epsilon = 0.0001);
+        assert_abs_diff_eq!(out.high, 5.0917, epsilon = 0.0001);
+
+        let out = odds_ratio_interval(0.0, 10.0, 5.0, 10.0, 1.96);
+        assert!(out.low > 0.0);
+        assert!(out.high.is_finite());
+    }
+
+    #[test]
+    fn maximum() {
+        assert_eq!(wilson_max(&[], 0.95), WilsonResult { low: 0.0, high: 1.0 });
+        assert_eq!(wilson_max(&[(3.0, 10.0)], 0.9), wilson_confidence(3.0, 10.0, 0.9));
+
+        let groups: Vec<(FP, FP)> = (0..10).map(|i| (40.0 + i as FP, 500.0)).collect();
+        let best = wilson_max(&groups, 0.95);
+        let naive = wilson_confidence(49.0, 500.0, 0.95);
+        assert!(best.low < naive.low);
+        assert!(best.high > naive.high);
+        assert!(best.width() > naive.width());
+        assert!(best.width() > wilson_max(&groups[..3], 0.95).width());
+
+        // A well-measured group can hold up the lower bound even when it is not the observed best
+        let best = wilson_max(&[(450.0, 1000.0), (3.0, 5.0)], 0.95);
+        assert!(best.low > wilson_confidence(3.0, 5.0, 0.95).low);
+    }
+
+    #[test]
+    fn ab_readout() {
+        // Newcombe (1998), table II
+        let out = diff_summary(56.0, 70.0, 48.0, 80.0, 0.95);
+        assert_abs_diff_eq!(out.difference, 20.0, epsilon = tol(1e-12) * 100.0);
+        assert_abs_diff_eq!(out.interval.low, 5.24, epsilon = 0.005);
+        assert_abs_diff_eq!(out.interval.high, 33.39, epsilon = 0.005);
+        assert!(out.significant);
+        assert_eq!(out.direction, Sign::Positive);
+
+        let out = diff_summary(5.0, 56.0, 0.0, 29.0, 0.95);
+        assert_abs_diff_eq!(out.interval.low, -3.81, epsilon = 0.005);
+        assert_abs_diff_eq!(out.interval.high, 19.26, epsilon = 0.005);
+        assert!(!out.significant);
+        assert_eq!(out.direction, Sign::Positive);
+
+        let swapped = diff_summary(48.0, 80.0, 56.0, 70.0, 0.95);
+        assert!(swapped.significant);
+        assert_eq!(swapped.direction, Sign::Negative);
+        assert_abs_diff_eq!(swapped.interval.high, -5.24, epsilon = 0.005);
+
+        let same = diff_summary(10.0, 100.0, 10.0, 100.0, 0.95);
+        assert_eq!(same.direction, Sign::Zero);
+        assert!(!same.significant);
+
+        for (s1, n1, s2, n2) in [(9.0, 10.0, 0.0, 0.0), (0.0, 0.0, 1.0, 10.0), (0.0, 0.0, 0.0, 0.0)] {
+            let empty = diff_summary(s1, n1, s2, n2, 0.95);
+            assert!(empty.difference.is_nan());
+            assert_eq!(empty.direction, Sign::Zero);
+            assert!(!empty.significant);
+        }
+    }
+
+    #[test]
+    fn all_pairs() {
+        let items = [(30.0, 100.0), (45.0, 100.0), (4.0, 10.0), (200.0, 1000.0), (3.0, 10.0)];
+        let m = wilson_compare_all(&items, 1.96);
+        assert_eq!(m.len(), items.len());
+        for (i, row) in m.iter().enumerate() {
+            assert_eq!(row.len(), items.len());
+            assert_eq!(row[i], 0.5);
+            for (j, &p) in row.iter().enumerate() {
+                assert_eq!(p + m[j][i], 1.0);
+                assert!((0.0..=1.0).contains(&p));
+            }
+        }
+        assert!(m[1][0] > 0.95);
+        assert!(m[0][3] > 0.95);
+        // Same observed rate, too little data to tell
+        assert!(m[0][4] > 0.3 && m[0][4] < 0.7);
+        assert_eq!(wilson_condorcet_winner(&items, 1.96), Some(1));
+
+        // Two equally good leaders: nobody beats everyone
+        let tied = [(50.0, 100.0), (50.0, 100.0), (10.0, 100.0)];
+        assert_eq!(wilson_compare_all(&tied, 1.96)[0][1], 0.5);
+        assert_eq!(wilson_condorcet_winner(&tied, 1.96), None);
+
+        assert_eq!(wilson_condorcet_winner(&[], 1.96), None);
+        assert_eq!(wilson_condorcet_winner(&[(1.0, 2.0)], 1.96), Some(0));
+        assert_eq!(wilson_compare_all(&[(5.0, 10.0), (5.0, 10.0)], 0.0)[0][1], 0.5);
+        assert_eq!(wilson_compare_all(&[(6.0, 10.0), (5.0, 10.0)], 0.0)[0][1], 1.0);
+    }
+
+    #[test]
+    fn joint_intervals() {
+        assert!(wilson_joint_intervals(&[], 0.95).is_empty());
+        assert_eq!(wilson_joint_intervals(&[(3.0, 10.0)], 0.9), [wilson_confidence(3.0, 10.0, 0.9)]);
+
+        let items = [(3.0, 10.0), (40.0, 500.0), (0.0, 20.0), (7.0, 7.0)];
+        let joint = wilson_joint_intervals(&items, 0.95);
+        let more = wilson_joint_intervals(&[&items[..], &items[..]].concat(), 0.95);
+        for (i, &(s, n)) in items.iter().enumerate() {
+            let single = wilson_confidence(s, n, 0.95);
+            assert!(joint[i].width() > single.width());
+            assert!(joint[i].low <= single.low + tol(0.0) && joint[i].high >= single.high - tol(0.0));
+            assert!(more[i].width() > joint[i].width());
+            // Šidák: the individual levels multiply up to the joint one
+            assert_eq!(joint[i], wilson_confidence(s, n, 0.95f64.powf(0.25) as FP));
+        }
+    }
+
+    #[test]
+    fn ranked_tail() {
+        for (s, n, z) in [(3.0, 10.0, 1.96), (990.0, 1000.0, 1.0), (0.0, 20.0, 2.576)] {
+            assert_eq!(wilson_ranked_tail(s, n, z, 1, 1), wilson(s, n, z));
+            // The worst of `k` is the Šidák joint interval
+            let joint = wilson_joint_intervals(&[(s, n); 4], confidence_from_z(z));
+            let tail = wilson_ranked_tail(s, n, z, 4, 1);
+            assert_abs_diff_eq!(tail.low, joint[0].low, epsilon = tol(1e-12));
+            assert_abs_diff_eq!(tail.high, joint[0].high, epsilon = tol(1e-12));
+            // Tolerating more misses narrows the interval again
+            let mut previous = tail;
+            for rank in 2..=4 {
+                let out = wilson_ranked_tail(s, n, z, 4, rank);
+                assert!(out.width() < previous.width());
+                previous = out;
+            }
+        }
+
+        // Rank 2 of 3 covers if at most one misses: `3q² − 2q³ = confidence`, solved with mpmath
+        let out = wilson_ranked_tail(30.0, 100.0, 1.96, 3, 2);
+        let expected = wilson_confidence(30.0, 100.0, 0.864_655_633_099_029_5);
+        assert_abs_diff_eq!(out.low, expected.low, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(out.high, expected.high, epsilon = tol(1e-12));
+    }
+
+    #[test]
+    fn three_cohorts() {
+        let cohorts = [
+            // new users: clear lift
+            (150.0, 1000.0, 100.0, 1000.0),
+            // returning users: no effect
+            (300.0, 1000.0, 305.0, 1000.0),
+            // power users: small negative effect
+            (400.0, 500.0, 420.0, 500.0),
+        ];
+        let out = wilson_cohort_comparison(&cohorts, 1.96);
+        assert_eq!(out.per_cohort.len(), 3);
+        assert!(out.per_cohort[0].low > 0.0);
+        assert!(out.per_cohort[1].low < 0.0 && out.per_cohort[1].high > 0.0);
+        assert!(out.per_cohort[2].high < 0.05);
+        assert!(out.pooled.low < 0.03 && out.pooled.high > 0.0);
+        assert!(out.homogeneity_p_value < 0.01);
+
+        let same = [(150.0, 1000.0, 100.0, 1000.0), (75.0, 500.0, 50.0, 500.0)];
+        let out = wilson_cohort_comparison(&same, 1.96);
+        assert_abs_diff_eq!(out.homogeneity_p_value, 1.0, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(out.pooled.low + out.pooled.high, 0.1, epsilon = tol(1e-6));
+
+        // All-or-nothing cohort has no variance, but still carries its Mantel–Haenszel weight
+        let out = wilson_cohort_comparison(&[(100.0, 100.0, 0.0, 100.0), (50.0, 100.0, 45.0, 100.0)], 1.96);
+        assert_abs_diff_eq!(out.pooled.low + out.pooled.high, 2.0 * 0.525, epsilon = tol(1e-9));
+        // 50² · (0.5·0.5 / 100 + 0.55·0.45 / 100) / 100²
+        let variance: FP = 0.00124375;
+        assert_abs_diff_eq!(out.pooled.high - out.pooled.low, 2.0 * 1.96 * variance.sqrt(), epsilon = tol(1e-6));
+        assert_eq!(out.homogeneity_p_value, 1.0);
+        let out = wilson_cohort_comparison(&[(150.0, 1000.0, 100.0, 1000.0), (0.0, 500.0, 0.0, 500.0)], 1.96);
+        assert_abs_diff_eq!(out.pooled.low + out.pooled.high, 2.0 * 0.05 * 500.0 / 750.0, epsilon = tol(1e-9));
+        let out = wilson_cohort_comparison(&[(0.0, 500.0, 0.0, 500.0)], 1.96);
+        assert_eq!(out.pooled, WilsonResult { low: 0.0, high: 0.0 });
+        assert_eq!(out.homogeneity_p_value, 1.0);
+    }
+
+    #[test]
+    fn homogeneity() {
+        // R: prop.test(c(83, 90, 129, 70), c(86, 93, 136, 82)) gives X-squared = 12.6, df = 3, p-value = 0.005585
+        let groups = [(83.0, 86.0), (90.0, 93.0), (129.0, 136.0), (70.0, 82.0)];
+        let out = homogeneity_test(&groups, TestKind::PearsonChiSquare).unwrap();
+        assert_eq!(out.df, 3);
+        assert_abs_diff_eq!(out.statistic, 12.600411297127659, epsilon = tol(1e-9) * 10.0);
+        assert_abs_diff_eq!(out.p_value, 0.005585476661626974, epsilon = tol(1e-9));
+
+        let out = homogeneity_test(&groups, TestKind::GTest).unwrap();
+        assert_eq!(out.df, 3);
+        assert_abs_diff_eq!(out.statistic, 10.667326800953848, epsilon = tol(1e-9) * 10.0);
+        assert_abs_diff_eq!(out.p_value, 0.01366774183946071, epsilon = tol(1e-9));
+
+        let with_empty = [(83.0, 86.0), (0.0, 0.0), (90.0, 93.0), (129.0, 136.0), (70.0, 82.0)];
+        assert_eq!(homogeneity_test(&with_empty, TestKind::GTest).unwrap(), out);
+
+        let same = homogeneity_test(&[(10.0, 100.0), (20.0, 200.0), (5.0, 50.0)], TestKind::PearsonChiSquare).unwrap();
+        assert_abs_diff_eq!(same.statistic, 0.0, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(same.p_value, 1.0, epsilon = tol(1e-9));
+        let none = homogeneity_test(&[(0.0, 10.0), (0.0, 20.0)], TestKind::GTest).unwrap();
+        assert_eq!((none.statistic, none.df, none.p_value), (0.0, 1, 1.0));
+
+        assert_eq!(homogeneity_test(&[(1.0, 2.0)], TestKind::GTest), Err(WilsonError::TooFewGroups));
+        assert_eq!(homogeneity_test(&[(1.0, 2.0), (0.0, 0.0)], TestKind::GTest), Err(WilsonError::TooFewGroups));
+        assert_eq!(
+            homogeneity_test(&[(1.0, 2.0), (3.0, 2.0)], TestKind::PearsonChiSquare),
+            Err(WilsonError::SuccessesExceedTrials)
+        );
+    }
+}
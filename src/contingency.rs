@@ -0,0 +1,175 @@
+//! Intervals derived from a 2×2 contingency table.
+//!
+//! Tables are laid out the usual epidemiological way:
+//!
+//! |           | outcome | no outcome |
+//! |-----------|---------|------------|
+//! | exposed   | `a`     | `b`        |
+//! | unexposed | `c`     | `d`        |
+
+use crate::compare::{odds_ratio_interval, proportion_difference_interval};
+use crate::{wilson, WilsonResult, FP};
+
+/// Wilson intervals for the share of each cell in the whole table, laid out as `[[a, b], [c, d]]`.
+#[must_use]
+pub fn wilson_contingency(a: FP, b: FP, c: FP, d: FP, z: FP) -> [[WilsonResult; 2]; 2] {
+    let total = a + b + c + d;
+    [
+        [wilson(a, total, z), wilson(b, total, z)],
+        [wilson(c, total, z), wilson(d, total, z)],
+    ]
+}
+
+/// Interval for the relative risk `(a / (a + b)) / (c / (c + d))` (Katz log method).
+///
+/// If `a` or `c` is zero, 0.5 is added to every cell.
+/// Unlike other results of this crate, bounds are within `[0, ∞)`.
+#[must_use]
+pub fn wilson_contingency_rr(a: FP, b: FP, c: FP, d: FP, z: FP) -> WilsonResult {
+    let (a, b, c, d) = if a == 0.0 || c == 0.0 {
+        (a + 0.5, b + 0.5, c + 0.5, d + 0.5)
+    } else {
+        (a, b, c, d)
+    };
+    let log_rr = (a / (a + b) / (c / (c + d))).ln();
+    let se = (1.0 / a - 1.0 / (a + b) + 1.0 / c - 1.0 / (c + d)).sqrt();
+    WilsonResult {
+        low: (log_rr - z * se).exp(),
+        high: (log_rr + z * se).exp(),
+    }
+}
+
+/// Interval for the odds ratio `a·d / (b·c)`, see [`odds_ratio_interval`].
+#[must_use]
+pub fn wilson_contingency_or(a: FP, b: FP, c: FP, d: FP, z: FP) -> WilsonResult {
+    odds_ratio_interval(a, a + b, c, c + d, z)
+}
+
+/// Interval for the risk difference `a / (a + b) − c / (c + d)`, see [`proportion_difference_interval`].
+#[must_use]
+pub fn wilson_contingency_rd(a: FP, b: FP, c: FP, d: FP, z: FP) -> WilsonResult {
+    proportion_difference_interval(a, a + b, c, c + d, z)
+}
+
+/// Alternative hypothesis of [`fisher_exact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alternative {
+    /// The two proportions differ
+    TwoSided,
+    /// The first proportion is lower than the second one
+    Less,
+    /// The first proportion is higher than the second one
+    Greater,
+}
+
+/// Fisher's exact test p-value for `s1` successes out of `n1` trials against `s2` out of `n2`.
+///
+/// Given the table margins, the number of successes in the first group follows the hypergeometric distribution.
+/// `Less` and `Greater` sum its lower or upper tail from the observed count; `TwoSided` sums probabilities of all tables
+/// not more likely than the observed one (with R's relative tolerance of `1e-7`), so results match R's `fisher.test`.
+/// Probabilities are built from ratios of consecutive terms, so there are no factorials to overflow;
+/// time and memory are proportional to `min(n1, n2)`.
+///
+/// Tables with a zero margin (no trials in a group, or no successes or failures at all) give `1`.
+/// Panics if successes exceed trials in any group.
+///
+/// ```
+/// use wilson::{fisher_exact, Alternative};
+/// // Lady tasting tea: 3 of 4 milk-first cups identified, 1 of 4 tea-first cups mistaken
+/// let p = fisher_exact(3, 4, 1, 4, Alternative::Greater);
+/// assert!((p - 17.0 / 70.0).abs() < 1e-6);
+/// ```
+#[must_use]
+pub fn fisher_exact(s1: u64, n1: u64, s2: u64, n2: u64, alternative: Alternative) -> FP {
+    assert!(s1 <= n1 && s2 <= n2, "successes must not exceed trials");
+    let successes = s1 + s2;
+    let total = n1 + n2;
+    if n1 == 0 || n2 == 0 || successes == 0 || successes == total {
+        return 1.0;
+    }
+    let lo = successes.saturating_sub(n2);
+    let hi = n1.min(successes);
+
+    // Unnormalized probabilities of `lo..=hi` successes in the first group, relative to the mode,
+    // from P(x + 1) / P(x) = (n1 − x)(m − x) / ((x + 1)(n2 − m + x + 1)).
+    let ratio = |x: u64| ((n1 - x) as FP * (successes - x) as FP) / ((x + 1) as FP * (n2 + x + 1 - successes) as FP);
+    let mode = (((n1 + 1) as FP * (successes + 1) as FP / (total + 2) as FP) as u64).clamp(lo, hi);
+    let mut weights = vec![0.0; (hi - lo + 1) as usize];
+    let at = |x: u64| (x - lo) as usize;
+    weights[at(mode)] = 1.0;
+    for x in mode..hi {
+        weights[at(x + 1)] = weights[at(x)] * ratio(x);
+    }
+    for x in (lo..mode).rev() {
+        weights[at(x)] = weights[at(x + 1)] / ratio(x);
+    }
+    let sum: FP = weights.iter().sum();
+    let observed = weights[at(s1)];
+    let tail: FP = match alternative {
+        Alternative::Less => weights[..=at(s1)].iter().sum(),
+        Alternative::Greater => weights[at(s1)..].iter().sum(),
+        Alternative::TwoSided => {
+            let limit = observed * (1.0 + 1e-7);
+            weights.iter().filter(|&&w| w <= limit).sum()
+        }
+    };
+    (tail / sum).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::tol;
+
+    #[test]
+    fn cells() {
+        let out = wilson_contingency(20.0, 80.0, 10.0, 90.0, 2.0);
+        assert_eq!(out[0][0], wilson(20.0, 200.0, 2.0));
+        assert_eq!(out[0][1], wilson(80.0, 200.0, 2.0));
+        assert_eq!(out[1][0], wilson(10.0, 200.0, 2.0));
+        assert_eq!(out[1][1], wilson(90.0, 200.0, 2.0));
+    }
+
+    #[test]
+    fn fisher() {
+        // (s1, n1, s2, n2, two-sided, less, greater) from R's fisher.test
+        let cases = [
+            (3, 4, 1, 4, 0.4857142857, 0.9857142857, 0.2428571429),
+            (2, 17, 10, 13, 0.0005367241191, 0.0004651809434, 0.999984519),
+            (1, 10, 9, 12, 0.003691355085, 0.003504235702, 0.9998979349),
+            (0, 5, 5, 5, 0.007936507937, 0.003968253968, 1.0),
+            (10, 100, 25, 100, 0.008503571479, 0.004251785739, 0.9987315877),
+            (7, 12, 2, 15, 0.03691838291, 0.9983688318, 0.01937452326),
+        ];
+        for (s1, n1, s2, n2, two_sided, less, greater) in cases {
+            assert_abs_diff_eq!(fisher_exact(s1, n1, s2, n2, Alternative::TwoSided), two_sided, epsilon = tol(1e-9));
+            assert_abs_diff_eq!(fisher_exact(s1, n1, s2, n2, Alternative::Less), less, epsilon = tol(1e-9));
+            assert_abs_diff_eq!(fisher_exact(s1, n1, s2, n2, Alternative::Greater), greater, epsilon = tol(1e-9));
+        }
+
+        assert_eq!(fisher_exact(0, 0, 3, 10, Alternative::TwoSided), 1.0);
+        assert_eq!(fisher_exact(0, 10, 0, 10, Alternative::Less), 1.0);
+        assert_eq!(fisher_exact(10, 10, 7, 7, Alternative::Greater), 1.0);
+
+        // Large tables stay finite and agree with a z-test in magnitude
+        let p = fisher_exact(5200, 100_000, 5000, 100_000, Alternative::TwoSided);
+        assert!(p > 0.03 && p < 0.05);
+    }
+
+    #[test]
+    fn effect_measures() {
+        let rr = wilson_contingency_rr(20.0, 80.0, 10.0, 90.0, 1.96);
+        assert_abs_diff_eq!(rr.low, 0.98655, epsilon = 0.0001);
+        assert_abs_diff_eq!(rr.high, 4.0545, epsilon = 0.0001);
+
+        let or = wilson_contingency_or(20.0, 80.0, 10.0, 90.0, 1.96);
+        assert_eq!(or, odds_ratio_interval(20.0, 100.0, 10.0, 100.0, 1.96));
+        assert!(or.low < 2.25 && or.high > 2.25);
+
+        let rd = wilson_contingency_rd(20.0, 80.0, 10.0, 90.0, 1.96);
+        assert_eq!(rd, proportion_difference_interval(20.0, 100.0, 10.0, 100.0, 1.96));
+        assert!(rd.low < 0.1 && rd.high > 0.1);
+    }
+}
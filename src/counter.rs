@@ -0,0 +1,553 @@
+//! Types accumulating observations over time.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::{wilson, WilsonResult, FP};
+
+/// Running totals of successes and trials with a fixed `z`, for streaming updates of the interval.
+///
+/// The Wilson interval is not a Bayesian posterior, so an update is simply a recalculation
+/// from the updated totals, which is as cheap as the calculation itself.
+///
+/// ```
+/// let mut acc = wilson::WilsonAccumulator::new(2.0);
+/// acc.update(3.0, 10.0);
+/// acc.update(7.0, 10.0);
+/// assert_eq!(acc.interval(), wilson::wilson(10.0, 20.0, 2.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WilsonAccumulator {
+    successes: FP,
+    trials: FP,
+    z: FP,
+}
+
+impl WilsonAccumulator {
+    /// Create an empty accumulator producing intervals for the given `z`.
+    #[must_use]
+    pub fn new(z: FP) -> WilsonAccumulator {
+        WilsonAccumulator {
+            successes: 0.0,
+            trials: 0.0,
+            z,
+        }
+    }
+
+    /// Add newly observed `new_s` successes out of `new_n` trials.
+    pub fn update(&mut self, new_s: FP, new_n: FP) {
+        self.successes += new_s;
+        self.trials += new_n;
+    }
+
+    /// Total successes so far.
+    #[must_use]
+    pub fn successes(&self) -> FP {
+        self.successes
+    }
+
+    /// Total trials so far.
+    #[must_use]
+    pub fn trials(&self) -> FP {
+        self.trials
+    }
+
+    /// Wilson interval for all the observations so far.
+    #[must_use]
+    pub fn interval(&self) -> WilsonResult {
+        wilson(self.successes, self.trials, self.z)
+    }
+}
+
+/// Integer counter of successes and trials, recording outcomes one by one.
+///
+/// ```
+/// let mut counter = wilson::WilsonCounter::new();
+/// counter.record(true);
+/// counter.record(false);
+/// assert_eq!(counter.interval(2.0), wilson::wilson(1.0, 2.0, 2.0));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WilsonCounter {
+    successes: u64,
+    trials: u64,
+}
+
+impl WilsonCounter {
+    /// Create a counter without any trials.
+    #[must_use]
+    pub fn new() -> WilsonCounter {
+        WilsonCounter::default()
+    }
+
+    /// Record outcome of one trial.
+    pub fn record(&mut self, success: bool) {
+        self.trials += 1;
+        if success {
+            self.successes += 1;
+        }
+    }
+
+    /// Number of successful trials recorded so far.
+    #[must_use]
+    pub fn successes(&self) -> u64 {
+        self.successes
+    }
+
+    /// Number of trials recorded so far.
+    #[must_use]
+    pub fn trials(&self) -> u64 {
+        self.trials
+    }
+
+    /// Wilson interval for the recorded outcomes.
+    #[must_use]
+    pub fn interval(&self, z: FP) -> WilsonResult {
+        wilson(self.successes as FP, self.trials as FP, z)
+    }
+}
+
+/// Lock-free version of [`WilsonCounter`] for recording outcomes from many threads at once.
+///
+/// Two independent atomic counters are used, so reading both of them is not a single atomic operation.
+/// [`load`](Self::load) is cheap, but the pair may mix states before and after concurrent
+/// [`record`](Self::record) calls (though successes never exceed trials).
+/// [`load_consistent`](Self::load_consistent) retries until it gets a pair that existed at some moment.
+#[derive(Debug, Default)]
+pub struct AtomicWilsonCounter {
+    successes: AtomicU64,
+    trials: AtomicU64,
+}
+
+impl AtomicWilsonCounter {
+    /// Create a counter without any trials.
+    #[must_use]
+    pub fn new() -> AtomicWilsonCounter {
+        AtomicWilsonCounter::default()
+    }
+
+    /// Record outcome of one trial.
+    pub fn record(&self, success: bool) {
+        self.trials.fetch_add(1, Ordering::Release);
+        if success {
+            self.successes.fetch_add(1, Ordering::Release);
+        }
+    }
+
+    /// Current `(successes, trials)`, possibly torn by concurrent [`record`](Self::record) calls.
+    #[must_use]
+    pub fn load(&self) -> (u64, u64) {
+        // Successes are incremented after trials, so reading them first keeps `successes <= trials`.
+        let successes = self.successes.load(Ordering::Acquire);
+        let trials = self.trials.load(Ordering::Acquire);
+        (successes, trials)
+    }
+
+    /// Current `(successes, trials)` without tearing: retries while other threads record outcomes concurrently.
+    ///
+    /// May spin for a while under heavy contention.
+    #[must_use]
+    pub fn load_consistent(&self) -> (u64, u64) {
+        loop {
+            let before = self.trials.load(Ordering::Acquire);
+            let successes = self.successes.load(Ordering::Acquire);
+            let trials = self.trials.load(Ordering::Acquire);
+            let successes_after = self.successes.load(Ordering::Acquire);
+            if before == trials && successes == successes_after && successes <= trials {
+                return (successes, trials);
+            }
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Wilson interval for the outcomes recorded so far, based on [`load`](Self::load).
+    #[must_use]
+    pub fn interval(&self, z: FP) -> WilsonResult {
+        let (successes, trials) = self.load();
+        wilson(successes as FP, trials as FP, z)
+    }
+}
+
+/// Fixed number of [`WilsonCounter`] buckets (e.g. one per hour) for producing a time series of intervals.
+///
+/// The caller drives time: outcomes go into the newest bucket or an explicit bucket index,
+/// and [`rotate`](Self::rotate) opens a new bucket, evicting the oldest one.
+/// See [`TimedWilsonCounter`] (`std` feature) for a version rotating by wall clock.
+///
+/// ```
+/// let mut counter = wilson::BucketedWilsonCounter::new(24);
+/// counter.record(true);
+/// counter.rotate();
+/// counter.record(false);
+/// let series = counter.series(2.0);
+/// assert_eq!(series.len(), 24);
+/// assert_eq!(series[22], wilson::wilson(1.0, 1.0, 2.0));
+/// assert_eq!(series[23], wilson::wilson(0.0, 1.0, 2.0));
+/// assert_eq!(counter.overall(2.0), wilson::wilson(1.0, 2.0, 2.0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BucketedWilsonCounter {
+    buckets: VecDeque<WilsonCounter>,
+}
+
+impl BucketedWilsonCounter {
+    /// Create `bucket_count` empty buckets. Panics if `bucket_count` is zero.
+    #[must_use]
+    pub fn new(bucket_count: usize) -> BucketedWilsonCounter {
+        assert!(bucket_count > 0, "at least one bucket is required");
+        BucketedWilsonCounter {
+            buckets: vec![WilsonCounter::new(); bucket_count].into(),
+        }
+    }
+
+    /// Number of retained buckets.
+    #[must_use]
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Record outcome of one trial into the newest bucket.
+    pub fn record(&mut self, success: bool) {
+        self.buckets.back_mut().expect("at least one bucket").record(success);
+    }
+
+    /// Record outcome of one trial into the bucket at `index`, counting from the oldest one.
+    ///
+    /// Panics if `index` is not less than [`bucket_count`](Self::bucket_count).
+    pub fn record_at(&mut self, index: usize, success: bool) {
+        self.buckets[index].record(success);
+    }
+
+    /// Open a new empty bucket, evicting the oldest one.
+    pub fn rotate(&mut self) {
+        self.buckets.pop_front();
+        self.buckets.push_back(WilsonCounter::new());
+    }
+
+    /// Counts of the bucket at `index`, counting from the oldest one.
+    ///
+    /// Panics if `index` is not less than [`bucket_count`](Self::bucket_count).
+    #[must_use]
+    pub fn bucket(&self, index: usize) -> WilsonCounter {
+        self.buckets[index]
+    }
+
+    /// Interval for each bucket, from the oldest to the newest.
+    #[must_use]
+    pub fn series(&self, z: FP) -> Vec<WilsonResult> {
+        self.buckets.iter().map(|b| b.interval(z)).collect()
+    }
+
+    /// Interval for all the outcomes in the retained buckets pooled together.
+    #[must_use]
+    pub fn overall(&self, z: FP) -> WilsonResult {
+        let (successes, trials) = self
+            .buckets
+            .iter()
+            .fold((0, 0), |(s, n), b| (s + b.successes(), n + b.trials()));
+        wilson(successes as FP, trials as FP, z)
+    }
+}
+
+/// [`BucketedWilsonCounter`] which rotates buckets of fixed duration by [`Instant`].
+///
+/// Buckets are aligned to the moment of creation. Idle periods longer than a bucket
+/// leave empty buckets behind, so the series always covers the last `bucket_count` periods.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct TimedWilsonCounter {
+    counter: BucketedWilsonCounter,
+    bucket_len: Duration,
+    bucket_start: Instant,
+}
+
+#[cfg(feature = "std")]
+impl TimedWilsonCounter {
+    /// Create `bucket_count` empty buckets, each covering `bucket_len`, with the newest one starting now.
+    ///
+    /// Panics if `bucket_count` or `bucket_len` is zero.
+    #[must_use]
+    pub fn new(bucket_count: usize, bucket_len: Duration) -> TimedWilsonCounter {
+        assert!(!bucket_len.is_zero(), "bucket duration must be positive");
+        TimedWilsonCounter {
+            counter: BucketedWilsonCounter::new(bucket_count),
+            bucket_len,
+            bucket_start: Instant::now(),
+        }
+    }
+
+    /// Rotate buckets so that the newest one covers `now`. Moments in the past are ignored.
+    pub fn advance_to(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.bucket_start);
+        let periods = elapsed.as_nanos() / self.bucket_len.as_nanos();
+        if periods == 0 {
+            return;
+        }
+        let rotations = periods.min(self.counter.bucket_count() as u128) as usize;
+        for _ in 0..rotations {
+            self.counter.rotate();
+        }
+        // `periods` fits into u32 unless buckets are nanoseconds long and the counter idled for years.
+        self.bucket_start += self.bucket_len * u32::try_from(periods).unwrap_or(u32::MAX);
+    }
+
+    /// Record outcome of one trial at the current moment.
+    pub fn record(&mut self, success: bool) {
+        self.advance_to(Instant::now());
+        self.counter.record(success);
+    }
+
+    /// Underlying buckets, as of the last [`record`](Self::record) or [`advance_to`](Self::advance_to).
+    #[must_use]
+    pub fn buckets(&self) -> &BucketedWilsonCounter {
+        &self.counter
+    }
+
+    /// Interval for each bucket up to now, from the oldest to the newest.
+    pub fn series(&mut self, z: FP) -> Vec<WilsonResult> {
+        self.advance_to(Instant::now());
+        self.counter.series(z)
+    }
+
+    /// Interval for all the outcomes in the retained buckets up to now.
+    pub fn overall(&mut self, z: FP) -> WilsonResult {
+        self.advance_to(Instant::now());
+        self.counter.overall(z)
+    }
+}
+
+/// Wilson interval for counts the caller has already decayed (weighted by recency).
+///
+/// Same as [`wilson`]: the interval treats the effective counts as if they were observed directly.
+/// See [`DecayCounter`] for keeping such counts.
+#[must_use]
+pub fn wilson_decayed(recent_successes: FP, recent_trials: FP, z: FP) -> WilsonResult {
+    wilson(recent_successes, recent_trials, z)
+}
+
+/// Counter where old outcomes fade away exponentially with the given half-life,
+/// e.g. "flagged posts in about the last week, weighted by recency".
+///
+/// Effective counts are `Σ 0.5^(age / half_life)` over trials (and over successful trials);
+/// they are kept as two numbers decayed lazily on each access, so memory use is constant.
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// let day = Duration::from_secs(86400);
+/// let start = Instant::now();
+/// let mut counter = wilson::DecayCounter::new(day);
+/// counter.record(true, start);
+/// counter.record(false, start + day);
+/// // The success is a day old, so it counts as half a trial by now
+/// assert_eq!(counter.successes(start + day), 0.5);
+/// assert_eq!(counter.trials(start + day), 1.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecayCounter {
+    half_life: Duration,
+    successes: FP,
+    trials: FP,
+    updated: Option<Instant>,
+}
+
+impl DecayCounter {
+    /// Create a counter without any trials. `Duration::MAX` as `half_life` disables the decay.
+    ///
+    /// Panics if `half_life` is zero.
+    #[must_use]
+    pub fn new(half_life: Duration) -> DecayCounter {
+        assert!(!half_life.is_zero(), "half-life must be positive");
+        DecayCounter {
+            half_life,
+            successes: 0.0,
+            trials: 0.0,
+            updated: None,
+        }
+    }
+
+    /// Factor by which counts from the last update shrink by `now`. Moments before the last update count as no time passing.
+    fn decay(&self, now: Instant) -> FP {
+        let Some(updated) = self.updated else {
+            return 1.0;
+        };
+        if self.half_life == Duration::MAX {
+            // Exactly no decay, rather than a factor one ulp below 1
+            return 1.0;
+        }
+        let elapsed = now.saturating_duration_since(updated);
+        (-(elapsed.as_secs_f64() / self.half_life.as_secs_f64()) as FP).exp2()
+    }
+
+    /// Record outcome of one trial happening at `now`.
+    pub fn record(&mut self, outcome: bool, now: Instant) {
+        let decay = self.decay(now);
+        self.successes *= decay;
+        self.trials *= decay;
+        self.trials += 1.0;
+        if outcome {
+            self.successes += 1.0;
+        }
+        self.updated = Some(self.updated.map_or(now, |u| u.max(now)));
+    }
+
+    /// Effective number of successful trials as of `now`.
+    #[must_use]
+    pub fn successes(&self, now: Instant) -> FP {
+        self.successes * self.decay(now)
+    }
+
+    /// Effective number of trials as of `now`.
+    #[must_use]
+    pub fn trials(&self, now: Instant) -> FP {
+        self.trials * self.decay(now)
+    }
+
+    /// Wilson interval for the effective counts as of `now`, see [`wilson_decayed`].
+    #[must_use]
+    pub fn interval(&self, z: FP, now: Instant) -> WilsonResult {
+        wilson_decayed(self.successes(now), self.trials(now), z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updates_match_bulk() {
+        let batches = [(1.0, 4.0), (0.0, 3.0), (5.0, 5.0), (2.0, 8.0), (0.5, 1.0)];
+        let mut acc = WilsonAccumulator::new(1.96);
+        assert_eq!(acc.interval(), wilson(0.0, 0.0, 1.96));
+        for &(s, n) in &batches {
+            acc.update(s, n);
+        }
+        assert_eq!(acc.successes(), 8.5);
+        assert_eq!(acc.trials(), 21.0);
+        assert_eq!(acc.interval(), wilson(8.5, 21.0, 1.96));
+    }
+
+    #[test]
+    fn counter() {
+        let mut counter = WilsonCounter::new();
+        assert_eq!(counter.interval(2.0), wilson(0.0, 0.0, 2.0));
+        for i in 0..20 {
+            counter.record(i % 4 == 0);
+        }
+        assert_eq!(counter.successes(), 5);
+        assert_eq!(counter.trials(), 20);
+        assert_eq!(counter.interval(2.0), wilson(5.0, 20.0, 2.0));
+    }
+
+    #[test]
+    fn atomic_counter_loses_nothing() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AtomicWilsonCounter>();
+
+        let counter = AtomicWilsonCounter::new();
+        std::thread::scope(|scope| {
+            for t in 0..8 {
+                let counter = &counter;
+                scope.spawn(move || {
+                    for i in 0..20000 {
+                        counter.record((i + t) % 5 == 0);
+                    }
+                });
+            }
+            scope.spawn(|| {
+                for _ in 0..1000 {
+                    let (s, n) = counter.load();
+                    assert!(s <= n);
+                    let (s, n) = counter.load_consistent();
+                    assert!(s <= n);
+                }
+            });
+        });
+        assert_eq!(counter.load(), (32000, 160000));
+        assert_eq!(counter.load_consistent(), (32000, 160000));
+        assert_eq!(counter.interval(2.0), wilson(32000.0, 160000.0, 2.0));
+    }
+
+    #[test]
+    fn bucketed_series() {
+        let mut counter = BucketedWilsonCounter::new(24);
+        for hour in 0..48u64 {
+            if hour > 0 {
+                counter.rotate();
+            }
+            for i in 0..(10 + hour) {
+                counter.record(i % (2 + hour % 5) == 0);
+            }
+        }
+        let expected = |hour: u64| {
+            let trials = 10 + hour;
+            let successes = (0..trials).filter(|i| i % (2 + hour % 5) == 0).count() as u64;
+            (successes, trials)
+        };
+
+        let series = counter.series(1.96);
+        assert_eq!(series.len(), 24);
+        let (mut total_s, mut total_n) = (0, 0);
+        for (i, interval) in series.iter().enumerate() {
+            let (s, n) = expected(24 + i as u64);
+            assert_eq!(counter.bucket(i).successes(), s);
+            assert_eq!(counter.bucket(i).trials(), n);
+            assert_eq!(*interval, wilson(s as FP, n as FP, 1.96));
+            total_s += s;
+            total_n += n;
+        }
+        assert_eq!(counter.overall(1.96), wilson(total_s as FP, total_n as FP, 1.96));
+
+        counter.record_at(0, true);
+        assert_eq!(counter.bucket(0).trials(), expected(24).1 + 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn timed_rotation() {
+        let hour = Duration::from_secs(3600);
+        let mut counter = TimedWilsonCounter::new(3, hour);
+        let start = Instant::now();
+        counter.record(true);
+        counter.advance_to(start + hour * 2 + hour / 2);
+        counter.advance_to(start);
+        assert_eq!(counter.buckets().bucket(0), WilsonCounter { successes: 1, trials: 1 });
+        assert_eq!(counter.buckets().bucket(2).trials(), 0);
+
+        counter.advance_to(start + hour * 100);
+        assert!((0..3).all(|i| counter.buckets().bucket(i).trials() == 0));
+    }
+
+    #[test]
+    fn decay() {
+        let start = Instant::now();
+        let hour = Duration::from_secs(3600);
+
+        let mut forever = DecayCounter::new(Duration::MAX);
+        let mut plain = WilsonCounter::new();
+        for i in 0..50u32 {
+            forever.record(i % 3 == 0, start + hour * i);
+            plain.record(i % 3 == 0);
+        }
+        let later = start + hour * 1000;
+        assert_eq!(forever.successes(later), plain.successes() as FP);
+        assert_eq!(forever.trials(later), plain.trials() as FP);
+        assert_eq!(forever.interval(2.0, later), plain.interval(2.0));
+
+        let mut counter = DecayCounter::new(hour);
+        assert_eq!(counter.interval(2.0, start), wilson(0.0, 0.0, 2.0));
+        for _ in 0..8 {
+            counter.record(true, start);
+        }
+        counter.record(false, start + hour * 2);
+        assert_eq!(counter.successes(start + hour * 2), 2.0);
+        assert_eq!(counter.trials(start + hour * 3), 1.5);
+        assert_eq!(counter.interval(1.96, start + hour * 3), wilson_decayed(1.0, 1.5, 1.96));
+        // Looking back in time does not undo the decay
+        assert_eq!(counter.trials(start), 3.0);
+
+        // Out of order records are treated as happening at the latest moment seen
+        counter.record(true, start);
+        assert_eq!(counter.trials(start + hour * 2), 4.0);
+    }
+}
@@ -0,0 +1,221 @@
+//! Exact coverage of the Wilson interval, `z` calibrated to guarantee it, and scoring of intervals as forecasts.
+
+use crate::gamma::ln_gamma;
+use crate::{confidence_from_z, wilson, WilsonResult, FP};
+
+/// Number of steps of the grid of true proportions `p = i / COVERAGE_GRID` checked by [`calibrated_z`].
+const COVERAGE_GRID: u32 = 1000;
+
+/// Binomial probabilities of covering `p` summed over the intervals of all outcomes.
+fn coverage_of(intervals: &[WilsonResult], ln_choose: &[FP], p: FP) -> FP {
+    let n = (intervals.len() - 1) as FP;
+    let (ln_p, ln_q) = (p.ln(), (1.0 - p).ln());
+    intervals
+        .iter()
+        .zip(ln_choose)
+        .enumerate()
+        .filter(|(_, (interval, _))| interval.low <= p && p <= interval.high)
+        .map(|(s, (_, &ln_c))| {
+            let s = s as FP;
+            // `0 · ln 0` is taken as 0, so that the ends of the range work
+            let successes = if s > 0.0 { s * ln_p } else { 0.0 };
+            let failures = if n - s > 0.0 { (n - s) * ln_q } else { 0.0 };
+            (ln_c + successes + failures).exp()
+        })
+        .sum::<FP>()
+        .min(1.0)
+}
+
+/// Intervals and log binomial coefficients for all outcomes `0..=trials`.
+fn outcomes(trials: u64, z: FP) -> (Vec<WilsonResult>, Vec<FP>) {
+    let n = trials as FP;
+    let intervals = (0..=trials).map(|s| wilson(s as FP, n, z)).collect();
+    let ln_n = ln_gamma(n + 1.0);
+    let ln_choose = (0..=trials)
+        .map(|s| ln_n - ln_gamma(s as FP + 1.0) - ln_gamma((trials - s) as FP + 1.0))
+        .collect();
+    (intervals, ln_choose)
+}
+
+/// Actual probability that the [`wilson`] interval for `trials` trials contains the true proportion `p`.
+///
+/// Sums binomial probabilities of all success counts whose interval contains `p`.
+/// For small `trials` this oscillates around the nominal level of `z` as `p` varies,
+/// dipping noticeably below it for some `p`. Time is proportional to `trials`.
+///
+/// ```
+/// let coverage = wilson::exact_coverage(10, 1.96, 0.3);
+/// assert!(coverage > 0.9 && coverage < 1.0);
+/// ```
+#[must_use]
+pub fn exact_coverage(trials: u64, z: FP, p: FP) -> FP {
+    let (intervals, ln_choose) = outcomes(trials, z);
+    coverage_of(&intervals, &ln_choose, p)
+}
+
+/// Smallest `z` for which the exact coverage of [`wilson`] intervals for `trials` trials
+/// is at least `confidence` for every true proportion on a grid of 999 points `0.001, 0.002, …, 0.999`.
+///
+/// Meant to be computed once per `trials` and cached; the result is passed to [`wilson`] as usual.
+/// Found by bisection over `z`, each step computing [`exact_coverage`] on the whole grid,
+/// so time is proportional to `trials`.
+/// Guaranteeing the level everywhere makes the intervals conservative, i.e. wider than with the nominal `z`.
+/// The worst cases are the dips of coverage near 0 and 1, just outside the interval of a single success
+/// (or failure), so for tiny `trials` the calibrated `z` is far above the nominal one.
+///
+/// Returns `0` for `confidence ≤ 0` or zero `trials` (whose interval is always `[0, 1]`),
+/// infinity for `confidence ≥ 1` or a level so close to 1 that the rounded coverage never reaches it,
+/// and NaN for NaN.
+///
+/// ```
+/// assert!(wilson::calibrated_z(10, 0.95) > 3.0);
+/// let z = wilson::calibrated_z(100, 0.95);
+/// assert!(z > 1.96 && z < 3.0);
+/// ```
+#[must_use]
+pub fn calibrated_z(trials: u64, confidence: FP) -> FP {
+    if confidence.is_nan() {
+        return FP::NAN;
+    }
+    if confidence <= 0.0 || trials == 0 {
+        return 0.0;
+    }
+    if confidence >= 1.0 {
+        return FP::INFINITY;
+    }
+    let ln_choose = outcomes(trials, 0.0).1;
+    let n = trials as FP;
+    let min_coverage = |z: FP| {
+        let intervals: Vec<WilsonResult> = (0..=trials).map(|s| wilson(s as FP, n, z)).collect();
+        (1..COVERAGE_GRID)
+            .map(|i| coverage_of(&intervals, &ln_choose, i as FP / COVERAGE_GRID as FP))
+            .fold(1.0, FP::min)
+    };
+    let mut lo = 0.0;
+    let mut hi = 1.0;
+    while min_coverage(hi) < confidence {
+        if (hi * hi).is_infinite() {
+            return FP::INFINITY;
+        }
+        lo = hi;
+        hi *= 2.0;
+    }
+    for _ in 0..50 {
+        let mid = 0.5 * (lo + hi);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if min_coverage(mid) < confidence {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+/// Interval score of `result` as a forecast of the later `observed` proportion (Gneiting & Raftery, 2007).
+///
+/// `IS = (high − low) + 2/α · (low − observed)` if `observed < low`, `+ 2/α · (observed − high)` if `observed > high`,
+/// with `α = 1 − confidence_from_z(z)` the nominal miss rate of the interval.
+/// Lower is better: it rewards narrow intervals, but penalizes misses by their distance, more so for higher confidence.
+/// It is a proper scoring rule, so averaged over many forecasts it is smallest for intervals with the nominal coverage.
+/// `z` should be the one `result` was calculated with.
+///
+/// ```
+/// let forecast = wilson::wilson(30.0, 100.0, 1.96);
+/// assert_eq!(wilson::wilson_interval_score(&forecast, 0.3, 1.96), forecast.width());
+/// assert!(wilson::wilson_interval_score(&forecast, 0.5, 1.96) > 1.0);
+/// ```
+#[must_use]
+pub fn wilson_interval_score(result: &WilsonResult, observed: FP, z: FP) -> FP {
+    let miss = if observed < result.low {
+        result.low - observed
+    } else if observed > result.high {
+        observed - result.high
+    } else {
+        // Checked separately, as the penalty is infinite for infinite `z`
+        return result.width();
+    };
+    let alpha = 1.0 - confidence_from_z(z);
+    result.width() + 2.0 / alpha * miss
+}
+
+/// [`wilson_interval_score`] of each forecast in `results` against the corresponding value in `observed`.
+///
+/// Panics if the slices have different lengths.
+#[must_use]
+pub fn wilson_interval_scores(results: &[WilsonResult], observed: &[FP], z: FP) -> Vec<FP> {
+    assert_eq!(results.len(), observed.len(), "each forecast needs one observation");
+    results
+        .iter()
+        .zip(observed)
+        .map(|(result, &observed)| wilson_interval_score(result, observed, z))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::tol;
+
+    #[test]
+    fn coverage() {
+        // Two trials at z = 1: outcomes 0 and 2 give [0, 1/3] and [2/3, 1], only the single success covers 0.5
+        assert_abs_diff_eq!(exact_coverage(2, 1.0, 0.5), 0.5, epsilon = tol(1e-12));
+        // At z = 0 the intervals are points s / n, covering p = 0.3 only by 3 successes out of 10
+        assert_abs_diff_eq!(exact_coverage(10, 0.0, 0.3), 0.26682793200000005, epsilon = tol(1e-9));
+        assert_abs_diff_eq!(exact_coverage(0, 1.96, 0.3), 1.0, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(exact_coverage(5, 1.96, 0.0), 1.0, epsilon = tol(1e-12));
+
+        // Coverage approaches the nominal level for large counts, on average over p
+        let average: FP = (1..20).map(|i| exact_coverage(2000, 1.96, i as FP / 20.0)).sum::<FP>() / 19.0;
+        assert_abs_diff_eq!(average, 0.95, epsilon = 0.005);
+    }
+
+    #[test]
+    fn interval_score() {
+        let forecast = WilsonResult { low: 0.2, high: 0.4 };
+        let z = 1.959963984540054;
+        // Inside, the score is just the width
+        for observed in [0.2, 0.3, 0.4] {
+            assert_abs_diff_eq!(wilson_interval_score(&forecast, observed, z), 0.2, epsilon = tol(1e-12));
+        }
+        // Outside, misses add 2/α = 40 per unit of distance, with α = 1 − 0.95 losing digits in single precision
+        assert_abs_diff_eq!(wilson_interval_score(&forecast, 0.1, z), 4.2, epsilon = 4.2 * tol(1e-9));
+        assert_abs_diff_eq!(wilson_interval_score(&forecast, 0.45, z), 2.2, epsilon = 2.2 * tol(1e-9));
+        assert!(wilson_interval_score(&forecast, 0.5, z) > wilson_interval_score(&forecast, 0.45, z));
+        // Lower confidence penalizes the same miss less
+        assert!(wilson_interval_score(&forecast, 0.1, 1.0) < wilson_interval_score(&forecast, 0.1, z));
+        assert_eq!(wilson_interval_score(&forecast, 0.3, FP::INFINITY), forecast.width());
+
+        let results = [forecast, wilson(3.0, 10.0, z)];
+        let scores = wilson_interval_scores(&results, &[0.5, 0.3], z);
+        assert_eq!(scores, [wilson_interval_score(&results[0], 0.5, z), results[1].width()]);
+        assert!(wilson_interval_scores(&[], &[], z).is_empty());
+    }
+
+    #[test]
+    fn calibration() {
+        let z = calibrated_z(10, 0.95);
+        assert!(z > 1.96);
+        let minimum = (1..COVERAGE_GRID)
+            .map(|i| exact_coverage(10, z, i as FP / COVERAGE_GRID as FP))
+            .fold(1.0, FP::min);
+        assert!(minimum >= 0.95);
+        // Slightly smaller `z` misses the target somewhere on the grid
+        let minimum = (1..COVERAGE_GRID)
+            .map(|i| exact_coverage(10, z * 0.999, i as FP / COVERAGE_GRID as FP))
+            .fold(1.0, FP::min);
+        assert!(minimum < 0.95);
+
+        assert_eq!(calibrated_z(0, 0.95), 0.0);
+        assert_eq!(calibrated_z(10, 0.0), 0.0);
+        assert_eq!(calibrated_z(10, 1.0), FP::INFINITY);
+        assert_eq!(calibrated_z(10, 1.0 - 2.0 * FP::EPSILON), FP::INFINITY);
+        assert!(calibrated_z(10, FP::NAN).is_nan());
+        assert!(calibrated_z(10, 0.99) > z);
+    }
+}
@@ -0,0 +1,486 @@
+//! Decisions against a threshold and planning how much data is needed for them.
+
+use core::cmp::Ordering;
+
+use crate::normal::normal_sf;
+use crate::{score_statistic, wilson, WilsonResult, FP};
+
+/// Where an interval lies relative to a threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// The whole interval is above the threshold
+    Above,
+    /// The whole interval is below the threshold
+    Below,
+    /// The interval contains the threshold, more data is needed
+    Inconclusive,
+}
+
+/// Outcome of [`trials_until_decision`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// Current data is already enough
+    AlreadyDecided(Decision),
+    /// This many additional trials (rounded up) are needed, provided the observed rate stays the same
+    After(FP),
+    /// The observed rate equals the threshold or is on the wrong side of it for the goal
+    /// (or there are no trials yet), so waiting would not help
+    Never,
+}
+
+impl WilsonResult {
+    /// Three-way version of checks like `low > threshold`: whether the whole interval is above
+    /// or below `threshold`, or it is too early to tell.
+    ///
+    /// ```
+    /// use wilson::{wilson, Decision};
+    /// assert_eq!(wilson(9.0, 10.0, 1.5).decision_ready(0.33), Decision::Above);
+    /// assert_eq!(wilson(1.0, 2.0, 1.5).decision_ready(0.33), Decision::Inconclusive);
+    /// ```
+    #[must_use]
+    pub fn decision_ready(&self, threshold: FP) -> Decision {
+        if self.low > threshold {
+            Decision::Above
+        } else if self.high < threshold {
+            Decision::Below
+        } else {
+            Decision::Inconclusive
+        }
+    }
+}
+
+fn decide(successes: FP, trials: FP, z: FP, threshold: FP) -> Decision {
+    wilson(successes, trials, z).decision_ready(threshold)
+}
+
+/// Project how many more trials are needed until the interval no longer contains `threshold`,
+/// assuming the observed rate `successes / trials` stays constant.
+///
+/// Either direction counts as a decision; see [`trials_until_decision_towards`] to wait for one of them only.
+/// Solved by bisection over the number of trials.
+///
+/// ```
+/// use wilson::{trials_until_decision, Projection};
+/// // Two flagged posts out of five: is the flag rate above 1/3?
+/// match trials_until_decision(2.0, 5.0, 1.5, 0.33) {
+///     Projection::After(more) => assert!(more > 10.0),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[must_use]
+pub fn trials_until_decision(successes: FP, trials: FP, z: FP, threshold: FP) -> Projection {
+    trials_until_decision_towards(successes, trials, z, threshold, Decision::Inconclusive)
+}
+
+/// Like [`trials_until_decision`], but only `goal` counts as a decision, e.g. [`Decision::Above`]
+/// for "how long until the lower bound clears the threshold". [`Decision::Inconclusive`] accepts either direction.
+///
+/// An observed rate on the wrong side of `threshold` for `goal` can only ever lead to the other decision,
+/// so it results in [`Projection::Never`], even if that other decision is already made.
+///
+/// ```
+/// use wilson::{trials_until_decision_towards, Decision, Projection};
+/// // One flagged post out of five will not show a flag rate above 1/3, however long we wait
+/// assert_eq!(trials_until_decision_towards(1.0, 5.0, 1.5, 0.33, Decision::Above), Projection::Never);
+/// ```
+#[must_use]
+pub fn trials_until_decision_towards(successes: FP, trials: FP, z: FP, threshold: FP, goal: Decision) -> Projection {
+    let reached = |decision: Decision| {
+        decision != Decision::Inconclusive && (goal == Decision::Inconclusive || decision == goal)
+    };
+    let current = decide(successes, trials, z, threshold);
+    if reached(current) {
+        return Projection::AlreadyDecided(current);
+    }
+    if trials <= 0.0 {
+        return Projection::Never;
+    }
+    let rate = successes / trials;
+    let side = rate.partial_cmp(&threshold);
+    let possible = match goal {
+        Decision::Above => side == Some(Ordering::Greater),
+        Decision::Below => side == Some(Ordering::Less),
+        Decision::Inconclusive => side != Some(Ordering::Equal),
+    };
+    if !possible {
+        return Projection::Never;
+    }
+    let decided = |n: FP| reached(decide(rate * n, n, z, threshold));
+
+    let mut lo = trials;
+    let mut hi = trials * 2.0;
+    while !decided(hi) {
+        lo = hi;
+        hi *= 2.0;
+        if !hi.is_finite() {
+            return Projection::Never;
+        }
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if decided(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Projection::After((hi - trials).ceil())
+}
+
+/// Minimum whole number of successes out of `trials` for which the Wilson lower bound exceeds `threshold`,
+/// i.e. for which `wilson(successes, trials, z).low > threshold`.
+///
+/// Answers questions like "how many flagged posts out of these should get a user banned".
+/// Returns infinity if even all the trials succeeding is not enough. Found by bisection,
+/// as the lower bound grows with the number of successes.
+///
+/// ```
+/// # use wilson::FP;
+/// // Ban if at least a third of posts get flagged, erring on the side of not banning (see `wilson`)
+/// assert_eq!(wilson::successes_to_exceed(2.0, 0.33, 1.5), 2.0);
+/// assert_eq!(wilson::successes_to_exceed(4.0, 0.33, 1.5), 3.0);
+/// assert_eq!(wilson::successes_to_exceed(1.0, 0.33, 1.5), FP::INFINITY);
+/// ```
+#[must_use]
+pub fn successes_to_exceed(trials: FP, threshold: FP, z: FP) -> FP {
+    let exceeds = |s: FP| wilson(s, trials, z).low > threshold;
+    let max = trials.floor();
+    if max.is_nan() || max < 0.0 || !exceeds(max) {
+        return FP::INFINITY;
+    }
+    if exceeds(0.0) {
+        return 0.0;
+    }
+    // Invariant: `lo` does not exceed the threshold, `hi` does
+    let (mut lo, mut hi) = (0.0, max);
+    while hi - lo > 1.0 {
+        let mid = (0.5 * (lo + hi)).floor();
+        if exceeds(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    hi
+}
+
+/// Smallest, possibly fractional, number of successes out of `trials` for which the Wilson lower bound
+/// exceeds `threshold`: `wilson(s, trials, z).low > threshold` holds for all `s` from the result up to `trials`.
+///
+/// The continuous counterpart of [`successes_to_exceed`], e.g. for weighted counts;
+/// see [`wilson_minimum_successes_int`] for whole trials. Found by bisection.
+/// Returns infinity if even all the trials succeeding is not enough, and `0` if no successes are needed.
+///
+/// ```
+/// let s = wilson::wilson_minimum_successes(100.0, 1.96, 0.3);
+/// assert!(s > 38.0 && s < 40.0);
+/// ```
+#[must_use]
+pub fn wilson_minimum_successes(trials: FP, z: FP, threshold: FP) -> FP {
+    let exceeds = |s: FP| wilson(s, trials, z).low > threshold;
+    if trials.is_nan() || trials < 0.0 || !exceeds(trials) {
+        return FP::INFINITY;
+    }
+    if exceeds(0.0) {
+        return 0.0;
+    }
+    let (mut lo, mut hi) = (0.0, trials);
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if exceeds(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    hi
+}
+
+/// Minimum whole number of successes out of `trials` for which the Wilson lower bound exceeds `threshold`,
+/// same as [`successes_to_exceed`] with integer counts.
+///
+/// Returns `trials + 1` (more successes than possible) if even all the trials succeeding is not enough.
+///
+/// ```
+/// // Ban if at least a third of posts get flagged, see `wilson`
+/// assert_eq!(wilson::wilson_minimum_successes_int(4, 1.5, 0.33), 3);
+/// assert_eq!(wilson::wilson_minimum_successes_int(1, 1.5, 0.33), 2);
+/// ```
+#[must_use]
+pub fn wilson_minimum_successes_int(trials: u64, z: FP, threshold: FP) -> u64 {
+    let s = successes_to_exceed(trials as FP, threshold, z);
+    if s.is_finite() {
+        s as u64
+    } else {
+        trials.saturating_add(1)
+    }
+}
+
+/// Minimum whole number of trials after which `wilson(rate · n, n, z).low >= threshold`,
+/// assuming the true rate is `observed_rate`.
+///
+/// Answers questions like "if the flag rate really is 40%, how many posts are needed before
+/// the lower bound clears 33%". Unlike [`trials_until_decision`], the successes scale with the trials
+/// and only the lower bound is considered. Solved by bisection, as the lower bound grows with the trials.
+/// Returns `None` if `observed_rate <= threshold`, as the lower bound then never gets there.
+///
+/// ```
+/// // Ban if at least a third of posts get flagged, see `wilson`
+/// let n = wilson::trials_for_lower_bound(0.4, 1.5, 0.33).unwrap();
+/// assert!(wilson::wilson(0.4 * n, n, 1.5).low >= 0.33);
+/// assert!(wilson::wilson(0.4 * (n - 1.0), n - 1.0, 1.5).low < 0.33);
+/// assert_eq!(wilson::trials_for_lower_bound(0.3, 1.5, 0.33), None);
+/// ```
+#[must_use]
+pub fn trials_for_lower_bound(observed_rate: FP, z: FP, threshold: FP) -> Option<FP> {
+    if observed_rate.is_nan() || observed_rate <= threshold {
+        return None;
+    }
+    let reached = |n: FP| wilson(observed_rate * n, n, z).low >= threshold;
+    if reached(0.0) {
+        return Some(0.0);
+    }
+    // Invariant: `lo` does not reach the threshold, `hi` does
+    let mut lo = 0.0;
+    let mut hi = 1.0;
+    while !reached(hi) {
+        lo = hi;
+        hi *= 2.0;
+        if !hi.is_finite() {
+            return None;
+        }
+    }
+    while hi - lo > 1.0 {
+        let mid = (0.5 * (lo + hi)).floor();
+        if reached(mid) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Some(hi)
+}
+
+/// One-sided p-value of the score test against the true rate being at most `threshold`:
+/// the confidence level at which the Wilson lower bound would touch `threshold`, taken from 1.
+fn p_value_approx(successes: FP, trials: FP, threshold: FP) -> FP {
+    if trials <= 0.0 {
+        return 1.0;
+    }
+    normal_sf(score_statistic(successes, trials, threshold))
+}
+
+/// Which `(successes, trials)` items have a rate above `threshold`, testing all of them at once while keeping
+/// the false discovery rate (expected fraction of wrongly passed items among passed ones) at `target_fdr`.
+///
+/// Uses the Benjamini–Hochberg procedure on one-sided p-values of [`score_statistic`](crate::score_statistic):
+/// with p-values sorted ascending, the items up to the largest rank `k` with `p₍ₖ₎ ≤ k / m · target_fdr` pass.
+/// This passes many more items than checking each lower bound at a Bonferroni-corrected `z`.
+/// Passed items also need `wilson(s, n, z).low > threshold`, which only matters for `z` beyond
+/// what the procedure requires anyway; `z = 0` gives the plain procedure.
+///
+/// ```
+/// let items = [(45.0, 100.0), (31.0, 100.0), (60.0, 100.0), (28.0, 100.0)];
+/// assert_eq!(wilson::wilson_fdr_adjust(&items, 0.3, 0.05, 0.0), [true, false, true, false]);
+/// ```
+#[must_use]
+pub fn wilson_fdr_adjust(items: &[(FP, FP)], threshold: FP, target_fdr: FP, z: FP) -> Vec<bool> {
+    let m = items.len();
+    let p_values: Vec<FP> = items.iter().map(|&(s, n)| p_value_approx(s, n, threshold)).collect();
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&a, &b| p_values[a].total_cmp(&p_values[b]));
+    let discoveries = (1..=m)
+        .rev()
+        .find(|&k| p_values[order[k - 1]] <= k as FP / m as FP * target_fdr)
+        .unwrap_or(0);
+    let mut passed = vec![false; m];
+    for &i in &order[..discoveries] {
+        let (s, n) = items[i];
+        passed[i] = wilson(s, n, z).low > threshold;
+    }
+    passed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decisions() {
+        let r = WilsonResult { low: 0.4, high: 0.6 };
+        assert_eq!(r.decision_ready(0.3), Decision::Above);
+        assert_eq!(r.decision_ready(0.7), Decision::Below);
+        assert_eq!(r.decision_ready(0.5), Decision::Inconclusive);
+        assert_eq!(r.decision_ready(0.4), Decision::Inconclusive);
+        assert_eq!(r.decision_ready(0.6), Decision::Inconclusive);
+    }
+
+    #[test]
+    fn projection_flips_decision() {
+        let (s, n, z, t) = (12.0, 30.0, 2.0, 0.3);
+        let Projection::After(more) = trials_until_decision(s, n, z, t) else {
+            panic!("expected a projection");
+        };
+        let total = n + more;
+        assert_eq!(decide(s / n * total, total, z, t), Decision::Above);
+        assert_eq!(decide(s / n * (total - 1.0), total - 1.0, z, t), Decision::Inconclusive);
+
+        let (s, n) = (6.0, 30.0);
+        let Projection::After(more) = trials_until_decision(s, n, z, t) else {
+            panic!("expected a projection");
+        };
+        let total = n + more;
+        assert_eq!(decide(s / n * total, total, z, t), Decision::Below);
+    }
+
+    #[test]
+    fn ban_thresholds() {
+        // Thresholds from the example of `wilson`: two flagged out of two get banned,
+        // but one non-flagged post requires three flagged ones. A single post is never enough.
+        assert_eq!(successes_to_exceed(1.0, 0.33, 1.5), FP::INFINITY);
+        assert_eq!(successes_to_exceed(2.0, 0.33, 1.5), 2.0);
+        assert_eq!(successes_to_exceed(3.0, 0.33, 1.5), 3.0);
+        assert_eq!(successes_to_exceed(4.0, 0.33, 1.5), 3.0);
+
+        for n in [1.0, 7.0, 10.0, 55.0, 1000.0] {
+            for t in [0.05, 0.33, 0.5, 0.9] {
+                let s = successes_to_exceed(n, t, 1.96);
+                if s.is_finite() {
+                    assert!(wilson(s, n, 1.96).low > t);
+                    assert!(s == 0.0 || wilson(s - 1.0, n, 1.96).low <= t);
+                } else {
+                    assert!(wilson(n, n, 1.96).low <= t);
+                }
+            }
+        }
+        assert_eq!(successes_to_exceed(10.0, -0.1, 1.96), 0.0);
+        assert_eq!(successes_to_exceed(0.0, 0.1, 1.96), FP::INFINITY);
+        assert_eq!(successes_to_exceed(FP::NAN, 0.1, 1.96), FP::INFINITY);
+    }
+
+    #[test]
+    fn minimum_successes() {
+        for n in [1.0, 7.0, 10.0, 55.5, 1000.0] {
+            for t in [0.05, 0.33, 0.5, 0.9] {
+                let s = wilson_minimum_successes(n, 1.96, t);
+                if s.is_finite() {
+                    assert!(wilson(s, n, 1.96).low > t);
+                    assert!(wilson(s * (1.0 - 1e-4), n, 1.96).low <= t);
+                    assert!(s < 1.0 || wilson(s - 1.0, n, 1.96).low <= t);
+                } else {
+                    assert!(wilson(n, n, 1.96).low <= t);
+                }
+            }
+        }
+        assert_eq!(wilson_minimum_successes(10.0, 1.96, -0.1), 0.0);
+        assert_eq!(wilson_minimum_successes(0.0, 1.96, 0.1), FP::INFINITY);
+
+        for n in [1u64, 2, 7, 10, 55, 1000] {
+            for t in [0.05, 0.33, 0.5, 0.9] {
+                let s = wilson_minimum_successes_int(n, 1.96, t);
+                if s <= n {
+                    assert!(wilson(s as FP, n as FP, 1.96).low > t);
+                    assert!(s == 0 || wilson((s - 1) as FP, n as FP, 1.96).low <= t);
+                    assert!(s as FP >= wilson_minimum_successes(n as FP, 1.96, t));
+                } else {
+                    assert_eq!(s, n + 1);
+                    assert!(wilson(n as FP, n as FP, 1.96).low <= t);
+                }
+            }
+        }
+        assert_eq!(wilson_minimum_successes_int(2, 1.5, 0.33), 2);
+        assert_eq!(wilson_minimum_successes_int(10, 1.96, -0.1), 0);
+    }
+
+    #[test]
+    fn trials_for_threshold() {
+        for (rate, z, t) in [(0.4, 1.5, 0.33), (0.5, 1.96, 0.1), (0.9, 2.576, 0.85), (1.0, 1.0, 0.5), (0.11, 1.96, 0.1)] {
+            let n = trials_for_lower_bound(rate, z, t).unwrap();
+            assert_eq!(n, n.floor());
+            assert!(wilson(rate * n, n, z).low >= t);
+            assert!(wilson(rate * (n - 1.0), n - 1.0, z).low < t);
+        }
+        assert_eq!(trials_for_lower_bound(0.4, 1.5, 0.4), None);
+        assert_eq!(trials_for_lower_bound(0.2, 1.5, 0.33), None);
+        assert_eq!(trials_for_lower_bound(FP::NAN, 1.5, 0.33), None);
+        assert_eq!(trials_for_lower_bound(0.4, 1.5, 0.0), Some(0.0));
+        // More confidence needs more trials
+        assert!(trials_for_lower_bound(0.4, 2.0, 0.33) > trials_for_lower_bound(0.4, 1.5, 0.33));
+    }
+
+    #[test]
+    fn false_discovery_rate() {
+        let items = [(45.0, 100.0), (31.0, 100.0), (60.0, 100.0), (28.0, 100.0), (38.0, 100.0)];
+        assert_eq!(wilson_fdr_adjust(&items, 0.3, 0.05, 0.0), [true, false, true, false, false]);
+        // More tolerance for false discoveries lets the weaker one through
+        assert_eq!(wilson_fdr_adjust(&items, 0.3, 0.2, 0.0), [true, false, true, false, true]);
+        // Lower bounds at a large `z` are stricter than the procedure
+        assert_eq!(wilson_fdr_adjust(&items, 0.3, 0.2, 3.0), [true, false, true, false, false]);
+        assert!(wilson_fdr_adjust(&[], 0.3, 0.05, 0.0).is_empty());
+        assert_eq!(wilson_fdr_adjust(&[(0.0, 0.0)], 0.3, 0.05, 0.0), [false]);
+
+        // Under the global null every discovery is false, so the FDR is the chance of any discovery
+        let mut rng = crate::bootstrap::SplitMix64(11);
+        let mut uniform = move || (rng.next() >> 11) as FP / (1u64 << 53) as FP;
+        let (target, replications) = (0.1, 400);
+        let mut any_discovery = 0;
+        for _ in 0..replications {
+            let items: Vec<(FP, FP)> = (0..20)
+                .map(|_| ((0..200).filter(|_| uniform() < 0.3).count() as FP, 200.0))
+                .collect();
+            if wilson_fdr_adjust(&items, 0.3, target, 0.0).contains(&true) {
+                any_discovery += 1;
+            }
+        }
+        let fdr = any_discovery as FP / replications as FP;
+        // Three standard errors of slack for the simulation
+        assert!(fdr <= target + 3.0 * (target * (1.0 - target) / replications as FP).sqrt());
+    }
+
+    #[test]
+    fn already_decided_or_never() {
+        assert_eq!(
+            trials_until_decision(90.0, 100.0, 2.0, 0.5),
+            Projection::AlreadyDecided(Decision::Above),
+        );
+        assert_eq!(
+            trials_until_decision(10.0, 100.0, 2.0, 0.5),
+            Projection::AlreadyDecided(Decision::Below),
+        );
+        assert_eq!(trials_until_decision(5.0, 10.0, 2.0, 0.5), Projection::Never);
+        assert_eq!(trials_until_decision(0.0, 0.0, 2.0, 0.5), Projection::Never);
+    }
+
+    #[test]
+    fn towards_goal() {
+        let (z, t) = (2.0, 0.3);
+        // Rate on the right side: the same projection as without a goal
+        assert_eq!(
+            trials_until_decision_towards(12.0, 30.0, z, t, Decision::Above),
+            trials_until_decision(12.0, 30.0, z, t),
+        );
+        assert_eq!(
+            trials_until_decision_towards(6.0, 30.0, z, t, Decision::Below),
+            trials_until_decision(6.0, 30.0, z, t),
+        );
+        // Rate on the wrong side, undecided or already decided the other way
+        assert_eq!(trials_until_decision_towards(6.0, 30.0, z, t, Decision::Above), Projection::Never);
+        assert_eq!(trials_until_decision_towards(12.0, 30.0, z, t, Decision::Below), Projection::Never);
+        assert_eq!(trials_until_decision_towards(10.0, 100.0, z, 0.5, Decision::Above), Projection::Never);
+        assert_eq!(trials_until_decision_towards(9.0, 30.0, z, t, Decision::Above), Projection::Never);
+        assert_eq!(
+            trials_until_decision_towards(90.0, 100.0, z, 0.5, Decision::Above),
+            Projection::AlreadyDecided(Decision::Above),
+        );
+        assert_eq!(
+            trials_until_decision_towards(10.0, 100.0, z, 0.5, Decision::Inconclusive),
+            Projection::AlreadyDecided(Decision::Below),
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Wilson interval in fixed-point arithmetic, for targets without a floating-point unit.
+
+use fixed::types::I32F32;
+
+/// Same as [`wilson`](crate::wilson), but computed entirely in [`I32F32`] integer arithmetic,
+/// returning `(low, high)`.
+///
+/// Results are within about `1e-8` of the floating-point calculation for moderate counts;
+/// the error grows to about `1e-6` around a million trials, as `successes / trials` gets
+/// multiplied back by large numbers. Intermediate values saturate instead of overflowing,
+/// so counts and `z²` must stay well below `2³⁰` for meaningful results.
+/// `trials ≤ 0` gives `(0, 1)`.
+///
+/// ```
+/// use fixed::types::I32F32;
+/// let (low, high) = wilson::wilson_fixed(I32F32::from_num(10), I32F32::from_num(20), I32F32::from_num(1.96));
+/// assert!((low.to_num::<f64>() - 0.299295).abs() < 1e-6);
+/// assert!((high.to_num::<f64>() - 0.700705).abs() < 1e-6);
+/// ```
+#[must_use]
+pub fn wilson_fixed(successes: I32F32, trials: I32F32, z: I32F32) -> (I32F32, I32F32) {
+    if trials <= I32F32::ZERO {
+        return (I32F32::ZERO, I32F32::ONE);
+    }
+    let n = trials;
+    let s = successes;
+    let z2 = z.saturating_mul(z);
+    let denominator = n.saturating_add(z2);
+    let p = s.saturating_add(z2 / 2) / denominator;
+    // `s / n` first, so that `s · (n − s)` cannot overflow
+    let variance = (s / n).saturating_mul(n - s).saturating_add(z2 / 4);
+    let d = z / denominator * variance.max(I32F32::ZERO).sqrt();
+    let clamp = |x: I32F32| x.clamp(I32F32::ZERO, I32F32::ONE);
+    (clamp(p - d), clamp(p + d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{wilson, FP};
+
+    #[test]
+    fn matches_wilson() {
+        let cases = [
+            (1.0, 2.0, 1.96),
+            (2.0, 10.0, 1.5),
+            (30.0, 100.0, 2.576),
+            (500.0, 1000.0, 1.0),
+            (0.5, 3.0, 1.96),
+            (17.0, 40.0, 0.0),
+            (0.0, 50.0, 1.96),
+            (9.0, 10.0, 5.0),
+            (12345.0, 1_000_000.0, 3.0),
+        ];
+        for (s, n, z) in cases {
+            let (low, high) = wilson_fixed(I32F32::from_num(s), I32F32::from_num(n), I32F32::from_num(z));
+            let reference = wilson(s as FP, n as FP, z as FP);
+            assert!((low.to_num::<FP>() - reference.low).abs() < 0.01);
+            assert!((high.to_num::<FP>() - reference.high).abs() < 0.01);
+            if cfg!(feature = "f64") {
+                assert!((low.to_num::<FP>() - reference.low).abs() < 1e-6);
+                assert!((high.to_num::<FP>() - reference.high).abs() < 1e-6);
+            }
+        }
+        let degenerate = (I32F32::ZERO, I32F32::ONE);
+        assert_eq!(wilson_fixed(I32F32::ZERO, I32F32::ZERO, I32F32::from_num(1.96)), degenerate);
+        assert_eq!(wilson_fixed(I32F32::ONE, I32F32::from_num(-3), I32F32::from_num(1.96)), degenerate);
+    }
+}
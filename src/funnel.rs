@@ -0,0 +1,90 @@
+//! Intervals for conversion funnels made of independent steps.
+
+use crate::{WilsonResult, FP};
+
+/// Interval for the end-to-end conversion of a funnel, i.e. the product of per-step proportions.
+///
+/// Each step is `(successes, trials)` and steps are assumed to be independent.
+/// Variances of the per-step log-proportions `(1 − p) / (n·p)` are summed (delta method)
+/// and the interval `ln(P) ± z·√V` is transformed back and clamped to `[0, 1]`.
+///
+/// Steps with zero successes or with all successes use the continuity-corrected proportion `(s + 0.5) / (n + 1)`,
+/// so the whole interval does not collapse to `[0, 0]` and a lone all-success step not to `[1, 1]`.
+/// Empty `steps` or any step with zero trials result in the `[0, 1]` interval.
+///
+/// For a single step this is the Wald interval on the log scale, which is close to [`wilson`](crate::wilson)
+/// for moderate counts, but less reliable for tiny ones.
+///
+/// ```
+/// // Visit → cart → checkout → purchase
+/// let out = wilson::funnel_interval(&[(2000.0, 10000.0), (600.0, 2000.0), (450.0, 600.0)], 1.96);
+/// assert!(out.low < 0.045 && 0.045 < out.high);
+/// assert!(out.low > 0.04 && out.high < 0.05);
+/// ```
+#[must_use]
+pub fn funnel_interval(steps: &[(FP, FP)], z: FP) -> WilsonResult {
+    if steps.is_empty() || steps.iter().any(|&(_, n)| n <= 0.0) {
+        return WilsonResult { low: 0.0, high: 1.0 };
+    }
+    let mut log_p = 0.0;
+    let mut variance = 0.0;
+    for &(s, n) in steps {
+        let (s, n) = if s <= 0.0 || s >= n { (s + 0.5, n + 1.0) } else { (s, n) };
+        let p = s / n;
+        log_p += p.ln();
+        variance += (1.0 - p) / (n * p);
+    }
+    let margin = z * variance.sqrt();
+    WilsonResult {
+        low: (log_p - margin).exp().clamp(0.0, 1.0),
+        high: (log_p + margin).exp().clamp(0.0, 1.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::wilson;
+
+    #[test]
+    fn single_step() {
+        let out = funnel_interval(&[(300.0, 1000.0)], 2.0);
+        let reference = wilson(300.0, 1000.0, 2.0);
+        assert_abs_diff_eq!(out.low, reference.low, epsilon = 0.005);
+        assert_abs_diff_eq!(out.high, reference.high, epsilon = 0.005);
+    }
+
+    #[test]
+    fn three_steps() {
+        let steps = [(5000.0, 100000.0), (1200.0, 5000.0), (30.0, 1200.0)];
+        let out = funnel_interval(&steps, 2.0);
+        let point = 0.05 * 0.24 * 0.025;
+        assert!(out.low < point && point < out.high);
+        // The narrow first step barely matters, the last one dominates the uncertainty
+        let last = funnel_interval(&steps[2..], 2.0);
+        assert_abs_diff_eq!(out.low / point, last.low / 0.025, epsilon = 0.02);
+        assert_abs_diff_eq!(out.high / point, last.high / 0.025, epsilon = 0.02);
+    }
+
+    #[test]
+    fn zero_successes() {
+        let out = funnel_interval(&[(100.0, 200.0), (0.0, 50.0)], 2.0);
+        assert!(out.high > 0.0);
+        assert!(out.low < out.high);
+        assert_eq!(funnel_interval(&[], 2.0), WilsonResult { low: 0.0, high: 1.0 });
+        assert_eq!(funnel_interval(&[(1.0, 2.0), (0.0, 0.0)], 2.0), WilsonResult { low: 0.0, high: 1.0 });
+    }
+
+    #[test]
+    fn all_successes() {
+        let out = funnel_interval(&[(50.0, 50.0)], 2.0);
+        assert!(out.low < 1.0 && out.low > 0.9);
+        assert_eq!(out.high, 1.0);
+        let out = funnel_interval(&[(100.0, 200.0), (50.0, 50.0)], 2.0);
+        let without = funnel_interval(&[(100.0, 200.0)], 2.0);
+        assert!(out.low < without.low && out.high < without.high);
+        assert!(out.high - out.low > without.high - without.low);
+    }
+}
@@ -0,0 +1,120 @@
+//! Gamma function family and chi-square distribution helpers.
+
+use crate::FP;
+
+/// Natural logarithm of the gamma function (Lanczos approximation, `g = 7`).
+pub(crate) fn ln_gamma(x: FP) -> FP {
+    // Digits beyond `f32` precision are there for `f64`
+    #[allow(clippy::excessive_precision)]
+    const COEFFS: [FP; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let pi = core::f64::consts::PI as FP;
+    if x < 0.5 {
+        // Reflection formula: Γ(x)·Γ(1 − x) = π / sin(πx)
+        return (pi / (pi * x).sin()).abs().ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let mut a = COEFFS[0];
+    let t = x + 7.5;
+    for (i, &c) in COEFFS.iter().enumerate().skip(1) {
+        a += c / (x + i as FP);
+    }
+    0.5 * (2.0 * pi).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+/// Natural logarithm of the beta function `B(a, b) = Γ(a)·Γ(b) / Γ(a + b)`.
+pub(crate) fn ln_beta(a: FP, b: FP) -> FP {
+    ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b)
+}
+
+/// Regularized upper incomplete gamma function `Q(a, x) = Γ(a, x) / Γ(a)`.
+pub(crate) fn gamma_q(a: FP, x: FP) -> FP {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    if !x.is_finite() {
+        return 0.0;
+    }
+    let log_prefactor = a * x.ln() - x - ln_gamma(a);
+    if x < a + 1.0 {
+        // Series for the lower function P(a, x)
+        let mut ap = a;
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        for _ in 0..1000 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * FP::EPSILON {
+                break;
+            }
+        }
+        1.0 - sum * log_prefactor.exp()
+    } else {
+        // Continued fraction for Q(a, x), modified Lentz's method
+        let tiny = FP::MIN_POSITIVE / FP::EPSILON;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as FP) * (i as FP - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < FP::EPSILON {
+                break;
+            }
+        }
+        log_prefactor.exp() * h
+    }
+}
+
+/// Upper tail `P(X > x)` of the chi-square distribution with `df` degrees of freedom.
+pub(crate) fn chi_square_sf(x: FP, df: FP) -> FP {
+    gamma_q(0.5 * df, 0.5 * x)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::tol;
+
+    #[test]
+    fn gamma() {
+        assert_abs_diff_eq!(ln_gamma(1.0), 0.0, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(ln_gamma(5.0), (24.0 as FP).ln(), epsilon = tol(1e-12));
+        assert_abs_diff_eq!(ln_gamma(0.5), 0.5723649429247001, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(ln_gamma(101.0), 363.73937555556347, epsilon = tol(1e-9));
+        assert_abs_diff_eq!(ln_beta(2.0, 3.0), -(12.0 as FP).ln(), epsilon = tol(1e-12));
+    }
+
+    #[test]
+    fn chi_square() {
+        assert_abs_diff_eq!(chi_square_sf(3.841458820694124, 1.0), 0.05, epsilon = tol(1e-10));
+        assert_abs_diff_eq!(chi_square_sf(5.991464547107979, 2.0), 0.05, epsilon = tol(1e-10));
+        assert_abs_diff_eq!(chi_square_sf(1.0, 3.0), 0.8012519569012009, epsilon = tol(1e-10));
+        assert_abs_diff_eq!(chi_square_sf(30.0, 4.0), 4.89443e-6, epsilon = tol(1e-10));
+        assert_eq!(chi_square_sf(0.0, 3.0), 1.0);
+    }
+}
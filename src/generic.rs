@@ -0,0 +1,184 @@
+//! Wilson interval for any floating-point type, independent of [`FP`](crate::FP).
+
+use core::ops::{Add, Div, Mul, Sub};
+
+/// Floating-point operations the Wilson interval needs, so that [`wilson`](crate::wilson)
+/// and `wilson_generic` share a single implementation.
+pub(crate) trait Real:
+    Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self>
+{
+    fn constant(x: f64) -> Self;
+    fn sqrt(self) -> Self;
+    fn mul_add(self, a: Self, b: Self) -> Self;
+    fn is_infinite(self) -> bool;
+}
+
+#[cfg(feature = "num-traits")]
+impl<F: num_traits::Float> Real for F {
+    fn constant(x: f64) -> Self {
+        F::from(x).expect("small constants are representable")
+    }
+
+    fn sqrt(self) -> Self {
+        num_traits::Float::sqrt(self)
+    }
+
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        num_traits::Float::mul_add(self, a, b)
+    }
+
+    fn is_infinite(self) -> bool {
+        num_traits::Float::is_infinite(self)
+    }
+}
+
+#[cfg(not(feature = "num-traits"))]
+impl Real for crate::FP {
+    fn constant(x: f64) -> Self {
+        x as crate::FP
+    }
+
+    fn sqrt(self) -> Self {
+        crate::FP::sqrt(self)
+    }
+
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        crate::FP::mul_add(self, a, b)
+    }
+
+    fn is_infinite(self) -> bool {
+        crate::FP::is_infinite(self)
+    }
+}
+
+/// Like `FP::clamp` to `[0, 1]`, keeps NaN.
+fn clamp_unit<F: Real>(x: F) -> F {
+    let (zero, one) = (F::constant(0.0), F::constant(1.0));
+    if x < zero {
+        zero
+    } else if x > one {
+        one
+    } else {
+        x
+    }
+}
+
+/// Adjusted center `p` and half-width `d` of the Wilson interval, `0.5 ± 0.5` for zero `trials`
+/// or `z` so huge that `z²` overflows.
+///
+/// `fused` uses fused multiply-add operations. Only the `fma` feature enables it for [`wilson`](crate::wilson):
+/// without hardware FMA support (e.g. on baseline x86_64) `mul_add` becomes a slow software routine, so it is opt-in.
+pub(crate) fn center_and_margin<F: Real>(successes: F, trials: F, z: F, fused: bool) -> (F, F) {
+    let half = F::constant(0.5);
+    if trials <= F::constant(0.001) || (z * z).is_infinite() {
+        return (half, half);
+    }
+    let n = trials;
+    let s = successes;
+    let variance = s * (n - s) / n;
+    if fused {
+        let denominator = z.mul_add(z, n);
+        let p = (half * z).mul_add(z, s) / denominator;
+        let d = z / denominator * (F::constant(0.25) * z).mul_add(z, variance).sqrt();
+        (p, d)
+    } else {
+        let p = (s + half * z * z) / (n + z * z);
+        let d = z / (n + z * z) * (variance + z * z / F::constant(4.0)).sqrt();
+        (p, d)
+    }
+}
+
+/// `(low, high)` of the Wilson interval, computed without subtracting nearly equal values.
+///
+/// `center − half_width` cancels catastrophically for few successes (and `center + half_width`
+/// rounds at 1 for few failures), so that rounding noise would make the bounds jitter instead of growing
+/// with `successes`. Multiplying through by the conjugate gives the equivalent
+/// `low = s² / (n·(n + z²)) / (center + half_width)` and, with failures `f = n − s`,
+/// `high = 1 − f² / (n·(n + z²)) / ((1 − center) + half_width)`, both free of cancellation.
+/// Below `0.5`, `high` is the plain sum instead: nothing cancels there, and `1 − …` would only be accurate
+/// to units of `EPSILON`, not relative to the tiny upper bounds of rare events.
+/// Zero `trials`, zero `z` or overflowing `z²` use `center ± half_width` directly.
+pub(crate) fn wilson_bounds<F: Real>(successes: F, trials: F, z: F, fused: bool) -> (F, F) {
+    let (zero, one, half) = (F::constant(0.0), F::constant(1.0), F::constant(0.5));
+    let (center, half_width) = center_and_margin(successes, trials, z, fused);
+    if trials <= F::constant(0.001) || z == zero || (z * z).is_infinite() {
+        return (clamp_unit(center - half_width), clamp_unit(center + half_width));
+    }
+    let (s, n) = (successes, trials);
+    let failures = n - s;
+    let z2 = z * z;
+    let scale = n * (n + z2);
+    let complement = (failures + half * z2) / (n + z2);
+    let low = s * s / scale / (center + half_width);
+    let direct = center + half_width;
+    let high = if direct < half {
+        direct
+    } else {
+        one - failures * failures / scale / (complement + half_width)
+    };
+    (clamp_unit(low), clamp_unit(high))
+}
+
+/// Same as [`wilson`](crate::wilson), but for any [`Float`](num_traits::Float) type, returning `(low, high)`.
+///
+/// Allows using both precisions in one binary, e.g. `f32` for bulk ranking and `f64` for reports,
+/// regardless of the `f32`/`f64` feature. Both share one implementation,
+/// so `wilson_generic::<FP>` matches [`wilson`](crate::wilson) exactly
+/// (except for the `fma` feature and the `f32` estimate adjustment, which apply to [`wilson`](crate::wilson) only).
+///
+/// ```
+/// let (low, high) = wilson::wilson_generic(10.0f32, 20.0, 1.96);
+/// assert!((low - 0.299295).abs() < 1e-6 && (high - 0.700705).abs() < 1e-6);
+/// ```
+#[cfg(feature = "num-traits")]
+#[must_use]
+pub fn wilson_generic<F: num_traits::Float>(successes: F, trials: F, z: F) -> (F, F) {
+    wilson_bounds(successes, trials, z, false)
+}
+
+#[cfg(all(test, feature = "num-traits"))]
+mod tests {
+    use super::*;
+    use crate::{wilson, FP};
+
+    const CASES: [(f64, f64, f64); 8] = [
+        (1.0, 2.0, 1.96),
+        (2.0, 10.0, 1.5),
+        (30.0, 100.0, 2.576),
+        (500.0, 1000.0, 1.0),
+        (0.5, 3.0, 1.96),
+        (17.0, 40.0, 0.0),
+        (3.0, 0.0, 1.96),
+        (9.0, 10.0, 5.0),
+    ];
+
+    #[test]
+    fn precisions_agree() {
+        for (s, n, z) in CASES {
+            let (low32, high32) = wilson_generic(s as f32, n as f32, z as f32);
+            let (low64, high64) = wilson_generic(s, n, z);
+            assert!((low64 - f64::from(low32)).abs() <= 4.0 * f64::from(f32::EPSILON));
+            assert!((high64 - f64::from(high32)).abs() <= 4.0 * f64::from(f32::EPSILON));
+        }
+    }
+
+    #[test]
+    fn matches_wilson() {
+        for (s, n, z) in CASES {
+            let (s, n, z) = (s as FP, n as FP, z as FP);
+            let (low, high) = wilson_generic(s, n, z);
+            let out = wilson(s, n, z);
+            assert!((low - out.low).abs() <= 4.0 * FP::EPSILON);
+            assert!((high - out.high).abs() <= 4.0 * FP::EPSILON);
+        }
+        // Bounds from the cancellation-free form, which a `p ± d` calculation misses in the last bits
+        if !cfg!(any(feature = "fma", feature = "f32")) {
+            for (s, n, z) in [(4.0, 10.0, 10.0), (999_999.0, 1e6, 1.96), (1.0, 1e9, 1.96)] {
+                let out = wilson(s, n, z);
+                assert_eq!(wilson_generic(s, n, z), (out.low, out.high));
+            }
+        }
+        assert!(wilson_generic(FP::NAN, 10.0, 1.96).0.is_nan());
+        assert_eq!(wilson_generic(2.0, 20.0, FP::MAX), (0.0, 1.0));
+    }
+}
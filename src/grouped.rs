@@ -0,0 +1,257 @@
+//! Intervals for keyed groups of counts.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{wilson, wilson_confidence, WilsonResult, FP};
+
+/// Calculate Wilson interval for each group of `(successes, trials)` counts, keeping the keys.
+///
+/// Groups with zero trials get the `[0, 1]` interval rather than being dropped.
+///
+/// ```
+/// use std::collections::HashMap;
+/// let mut counts = HashMap::new();
+/// counts.insert("de", (10, 20));
+/// counts.insert("fr", (0, 0));
+/// let out = wilson::wilson_grouped(&counts, 2.0);
+/// assert_eq!(out["de"], wilson::wilson(10.0, 20.0, 2.0));
+/// assert_eq!(out["fr"].high, 1.0);
+/// ```
+#[must_use]
+pub fn wilson_grouped<K: Eq + Hash + Clone>(counts: &HashMap<K, (u64, u64)>, z: FP) -> HashMap<K, WilsonResult> {
+    counts
+        .iter()
+        .map(|(k, &(s, n))| (k.clone(), wilson(s as FP, n as FP, z)))
+        .collect()
+}
+
+/// Collection-agnostic version of [`wilson_grouped`].
+///
+/// Every input item produces one output item in the same order.
+/// Duplicate keys are not merged.
+#[must_use]
+pub fn wilson_grouped_iter<K, I>(counts: I, z: FP) -> Vec<(K, WilsonResult)>
+where
+    I: IntoIterator<Item = (K, (u64, u64))>,
+{
+    counts
+        .into_iter()
+        .map(|(k, (s, n))| (k, wilson(s as FP, n as FP, z)))
+        .collect()
+}
+
+/// Like [`wilson_grouped`], but tallies the counts from labeled outcomes in a single pass,
+/// e.g. conversions per country from a stream of `(country, converted)` events.
+///
+/// Only keys that occur get an interval, so empty input gives an empty map.
+///
+/// ```
+/// let events = [("de", true), ("fr", false), ("de", false), ("de", true)];
+/// let out = wilson::wilson_grouped_outcomes(events.into_iter(), 2.0);
+/// assert_eq!(out["de"], wilson::wilson(2.0, 3.0, 2.0));
+/// assert_eq!(out["fr"], wilson::wilson(0.0, 1.0, 2.0));
+/// ```
+#[must_use]
+pub fn wilson_grouped_outcomes<K: Eq + Hash>(
+    outcomes: impl Iterator<Item = (K, bool)>,
+    z: FP,
+) -> HashMap<K, WilsonResult> {
+    let mut counts: HashMap<K, (u64, u64)> = HashMap::new();
+    for (key, outcome) in outcomes {
+        let (s, n) = counts.entry(key).or_default();
+        *s += u64::from(outcome);
+        *n += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(k, (s, n))| (k, wilson(s as FP, n as FP, z)))
+        .collect()
+}
+
+/// Like [`wilson_grouped`], but additionally returns the interval for all groups pooled together.
+#[must_use]
+pub fn wilson_grouped_pooled<K: Eq + Hash + Clone>(
+    counts: &HashMap<K, (u64, u64)>,
+    z: FP,
+) -> (HashMap<K, WilsonResult>, WilsonResult) {
+    let (s, n) = counts
+        .values()
+        .fold((0.0, 0.0), |(s, n), &(gs, gn)| (s + gs as FP, n + gn as FP));
+    (wilson_grouped(counts, z), wilson(s, n, z))
+}
+
+/// Partial pooling of an individual's interval with the interval of its group.
+///
+/// `group_data` are `(successes, trials)` of all members of the group, pooled into one group-level interval.
+/// The result blends midpoints and half-widths of the individual and group intervals linearly:
+/// `shrinkage = 0` gives the pure individual interval, `shrinkage = 1` the group one.
+/// `shrinkage` is clamped to `[0, 1]`.
+///
+/// This is a simple heuristic rather than a fitted hierarchical model; choose larger `shrinkage`
+/// for individuals with little data of their own.
+#[must_use]
+pub fn wilson_hierarchical(
+    group_data: &[(FP, FP)],
+    individual_s: FP,
+    individual_n: FP,
+    z: FP,
+    shrinkage: FP,
+) -> WilsonResult {
+    let k = shrinkage.clamp(0.0, 1.0);
+    let (gs, gn) = group_data
+        .iter()
+        .fold((0.0, 0.0), |(s, n), &(ds, dn)| (s + ds, n + dn));
+    let group = wilson(gs, gn, z);
+    let individual = wilson(individual_s, individual_n, z);
+    WilsonResult {
+        low: (1.0 - k) * individual.low + k * group.low,
+        high: (1.0 - k) * individual.high + k * group.high,
+    }
+}
+
+/// Result of [`analyze`].
+#[derive(Debug, PartialEq)]
+pub struct Analysis {
+    /// Interval for all groups pooled together
+    pub pooled: WilsonResult,
+    /// Interval for each group, in the input order
+    pub groups: Vec<WilsonResult>,
+    /// Index of the group with the highest lower bound (the first one on ties), `None` for no groups
+    pub best: Option<usize>,
+}
+
+/// Compute everything about a table of `(successes, trials)` groups at once:
+/// the pooled interval, per-group intervals and the group ranking first by lower bound.
+///
+/// All intervals use [`wilson_confidence`] with the given two-sided `confidence`.
+///
+/// ```
+/// let analysis = wilson::analyze(&[(5.0, 10.0), (60.0, 100.0), (1.0, 1.0)], 0.95);
+/// assert_eq!(analysis.best, Some(1));
+/// assert_eq!(analysis.pooled, wilson::wilson_confidence(66.0, 111.0, 0.95));
+/// ```
+#[must_use]
+pub fn analyze(data: &[(FP, FP)], confidence: FP) -> Analysis {
+    let groups: Vec<WilsonResult> = data.iter().map(|&(s, n)| wilson_confidence(s, n, confidence)).collect();
+    let (s, n) = data.iter().fold((0.0, 0.0), |(s, n), &(ds, dn)| (s + ds, n + dn));
+    let mut best: Option<usize> = None;
+    for (i, group) in groups.iter().enumerate() {
+        if best.is_none_or(|b| group.low > groups[b].low) {
+            best = Some(i);
+        }
+    }
+    Analysis {
+        pooled: wilson_confidence(s, n, confidence),
+        groups,
+        best,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grouped_map() {
+        let mut counts = HashMap::new();
+        counts.insert("a".to_owned(), (1, 2));
+        counts.insert("b".to_owned(), (10, 20));
+        counts.insert("c".to_owned(), (0, 0));
+        let out = wilson_grouped(&counts, 2.0);
+        assert_eq!(out.len(), 3);
+        assert_eq!(out["a"], wilson(1.0, 2.0, 2.0));
+        assert_eq!(out["b"], wilson(10.0, 20.0, 2.0));
+        assert_eq!(out["c"], wilson(0.0, 0.0, 2.0));
+
+        let (groups, pooled) = wilson_grouped_pooled(&counts, 2.0);
+        assert_eq!(groups, out);
+        assert_eq!(pooled, wilson(11.0, 22.0, 2.0));
+    }
+
+    #[test]
+    fn empty() {
+        let counts: HashMap<u32, (u64, u64)> = HashMap::new();
+        assert!(wilson_grouped(&counts, 2.0).is_empty());
+        let (groups, pooled) = wilson_grouped_pooled(&counts, 2.0);
+        assert!(groups.is_empty());
+        assert_eq!(pooled, wilson(0.0, 0.0, 2.0));
+        assert!(wilson_grouped_iter(Vec::<(u32, (u64, u64))>::new(), 2.0).is_empty());
+    }
+
+    #[test]
+    fn labeled_outcomes() {
+        let events = [
+            ("us", true),
+            ("de", false),
+            ("us", false),
+            ("jp", true),
+            ("us", true),
+            ("de", false),
+            ("us", true),
+        ];
+        let out = wilson_grouped_outcomes(events.iter().copied(), 1.96);
+        assert_eq!(out.len(), 3);
+        assert_eq!(out["us"], wilson(3.0, 4.0, 1.96));
+        assert_eq!(out["de"], wilson(0.0, 2.0, 1.96));
+        assert_eq!(out["jp"], wilson(1.0, 1.0, 1.96));
+
+        // Same as tallying first
+        let mut counts = HashMap::new();
+        for &(key, outcome) in &events {
+            let entry = counts.entry(key).or_insert((0, 0));
+            entry.0 += u64::from(outcome);
+            entry.1 += 1;
+        }
+        assert_eq!(counts["us"], (3, 4));
+        assert_eq!(out, wilson_grouped(&counts, 1.96));
+
+        assert!(wilson_grouped_outcomes(core::iter::empty::<(u8, bool)>(), 1.96).is_empty());
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let out = wilson_grouped_iter([("x", (1, 2)), ("y", (2, 20)), ("x", (10, 20))], 2.0);
+        assert_eq!(out.len(), 3);
+        assert_eq!(out[0], ("x", wilson(1.0, 2.0, 2.0)));
+        assert_eq!(out[1], ("y", wilson(2.0, 20.0, 2.0)));
+        assert_eq!(out[2], ("x", wilson(10.0, 20.0, 2.0)));
+    }
+
+    #[test]
+    fn hierarchical() {
+        let group = [(30.0, 100.0), (50.0, 100.0), (10.0, 100.0)];
+        assert_eq!(wilson_hierarchical(&group, 4.0, 5.0, 2.0, 0.0), wilson(4.0, 5.0, 2.0));
+        assert_eq!(wilson_hierarchical(&group, 4.0, 5.0, 2.0, 1.0), wilson(90.0, 300.0, 2.0));
+
+        let mut previous = wilson_hierarchical(&group, 4.0, 5.0, 2.0, 0.0);
+        for i in 1..=10 {
+            let out = wilson_hierarchical(&group, 4.0, 5.0, 2.0, i as FP / 10.0);
+            assert!(out.low <= previous.low);
+            assert!(out.high <= previous.high);
+            assert!(out.width() <= previous.width());
+            previous = out;
+        }
+    }
+
+    #[test]
+    fn analysis() {
+        // Posts flagged per user
+        let data = [(2.0, 10.0), (1.0, 2.0), (30.0, 100.0), (0.0, 0.0), (33.0, 110.0)];
+        let out = analyze(&data, 0.9);
+        assert_eq!(out.groups.len(), 5);
+        for (&(s, n), group) in data.iter().zip(&out.groups) {
+            assert_eq!(*group, wilson_confidence(s, n, 0.9));
+        }
+        assert_eq!(out.pooled, wilson_confidence(66.0, 222.0, 0.9));
+        // 33 of 110 is a tie with 30 of 100 at the estimate, but has the higher lower bound
+        assert_eq!(out.best, Some(4));
+
+        let tied = analyze(&[(1.0, 2.0), (1.0, 2.0)], 0.95);
+        assert_eq!(tied.best, Some(0));
+        let empty = analyze(&[], 0.95);
+        assert!(empty.groups.is_empty());
+        assert_eq!(empty.best, None);
+        assert_eq!(empty.pooled, wilson_confidence(0.0, 0.0, 0.95));
+    }
+}
@@ -2,6 +2,90 @@
 
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
+// Reference values in tests are written with `f64` precision
+#![cfg_attr(all(test, feature = "f32"), allow(clippy::excessive_precision))]
+
+use core::ops::{Bound, RangeBounds, RangeInclusive};
+
+mod bayes;
+mod bootstrap;
+mod change;
+mod compare;
+mod contingency;
+mod counter;
+mod coverage;
+mod decision;
+#[cfg(feature = "fast-math")]
+mod fast;
+#[cfg(feature = "fixed-point")]
+mod fixed_point;
+mod funnel;
+mod gamma;
+mod generic;
+mod grouped;
+mod likelihood;
+mod normal;
+mod overdispersion;
+#[cfg(feature = "plotters")]
+mod plot;
+mod power;
+mod rank;
+mod sequential;
+#[cfg(feature = "futures")]
+mod stream;
+mod summary;
+mod table;
+
+pub use bayes::prob_b_beats_a;
+#[cfg(feature = "rand")]
+pub use bootstrap::{bootstrap_interval, bootstrap_interval_weighted};
+pub use bootstrap::{wilson_boot_ci, wilson_jackknife};
+pub use change::{detect_change, ChangePoint};
+pub use compare::{
+    diff_summary, homogeneity_test, odds_ratio_interval, proportion_difference_interval, wilson_cohort_comparison,
+    wilson_compare_all, wilson_condorcet_winner, wilson_joint_intervals, wilson_max, wilson_ranked_tail,
+    CohortComparison, DiffSummary, HomogeneityTest, Sign, TestKind,
+};
+pub use contingency::{fisher_exact, wilson_contingency, wilson_contingency_or, wilson_contingency_rd, wilson_contingency_rr, Alternative};
+pub use counter::{
+    wilson_decayed, AtomicWilsonCounter, BucketedWilsonCounter, DecayCounter, WilsonAccumulator, WilsonCounter,
+};
+#[cfg(feature = "std")]
+pub use counter::TimedWilsonCounter;
+pub use coverage::{calibrated_z, exact_coverage, wilson_interval_score, wilson_interval_scores};
+pub use decision::{
+    successes_to_exceed, trials_for_lower_bound, trials_until_decision, trials_until_decision_towards,
+    wilson_fdr_adjust, wilson_minimum_successes, wilson_minimum_successes_int, Decision, Projection,
+};
+#[cfg(feature = "fast-math")]
+pub use fast::wilson_lower_fast;
+#[cfg(feature = "fixed-point")]
+pub use fixed_point::wilson_fixed;
+pub use funnel::funnel_interval;
+#[cfg(feature = "num-traits")]
+pub use generic::wilson_generic;
+pub use grouped::{
+    analyze, wilson_grouped, wilson_grouped_iter, wilson_grouped_outcomes, wilson_grouped_pooled, wilson_hierarchical,
+    Analysis,
+};
+pub use likelihood::profile_likelihood_interval;
+#[cfg(feature = "plotters")]
+pub use plot::{draw_intervals, DrawError, IntervalStyle};
+pub use overdispersion::{beta_binomial_interval, wilson_kfold, wilson_overdispersed, OverdispersedResult};
+pub use power::{
+    power_two_proportions, required_n_two_proportions, wilson_expected_width, wilson_expected_width_worst_case,
+    wilson_precision_to_trials, wilson_precision_to_trials_conservative, wilson_width_table,
+};
+pub use rank::{wilson_rank_stability, wilson_rank_stability_score};
+pub use sequential::{sequential_boundaries, Spending};
+#[cfg(feature = "futures")]
+pub use stream::{RunningWilson, WilsonStreamExt};
+pub use summary::{interval_summary, IntervalSummary};
+pub use table::WilsonTable;
+#[cfg(feature = "small-table")]
+pub use table::wilson_lower_bound_small;
+#[doc(hidden)]
+pub use table::{__fill_bounds, __fill_per_mille, __table_len};
 
 #[cfg(feature = "f64")]
 /// Floating-point type used in this crate. Can be configured to f32 or to f64 depending on mutually exclusive Cargo features.
@@ -14,6 +98,7 @@ pub type FP = f32;
 /// 
 /// Next "trial" is expected to be "success" with probability from `low` to `high` with a confidence that depend on `z` parameter.
 #[derive(Debug,PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WilsonResult {
     /// Lower bound of a Wilson confidence interval
     pub low: FP,
@@ -21,6 +106,325 @@ pub struct WilsonResult {
     pub high: FP,
 }
 
+impl WilsonResult {
+    /// Interval as an inclusive range `low..=high`.
+    #[must_use]
+    pub fn as_range(&self) -> RangeInclusive<FP> {
+        self.low..=self.high
+    }
+
+    /// Middle of the interval, `(low + high) / 2`.
+    #[must_use]
+    pub fn midpoint(&self) -> FP {
+        0.5 * (self.low + self.high)
+    }
+
+    /// Distance between `low` and `high`.
+    #[must_use]
+    pub fn width(&self) -> FP {
+        self.high - self.low
+    }
+
+    /// Heuristic score from 0 (know nothing, `[0, 1]` interval) to 1 (very narrow interval): `1 - width`.
+    ///
+    /// Handy for ranking estimates by how much data backs them, but it is not a statistical quantity by itself.
+    #[must_use]
+    pub fn precision(&self) -> FP {
+        (1.0 - self.width()).clamp(0.0, 1.0)
+    }
+
+    /// Information content of the interval in bits, approximated as `-log2(width)`:
+    /// 0 for the know-nothing `[0, 1]` interval, one more bit for each halving of the width.
+    #[must_use]
+    pub fn wilson_information(&self) -> FP {
+        -self.width().log2()
+    }
+
+    /// Approximate standard error of the proportion estimate: average half-width divided by `z`.
+    ///
+    /// The Wilson interval is not symmetric, so this is only an approximation,
+    /// close to `sqrt(p·(1 − p) / n)` for moderate `trials`.
+    /// `z` must be the same value the interval was calculated with.
+    #[must_use]
+    pub fn to_standard_error(&self, z: FP) -> FP {
+        0.5 * self.width() / z
+    }
+
+    /// Approximate variance of the proportion estimate, the square of [`to_standard_error`](Self::to_standard_error).
+    #[must_use]
+    pub fn to_variance(&self, z: FP) -> FP {
+        let se = self.to_standard_error(z);
+        se * se
+    }
+
+    /// Approximate interval for the proportion of successes among `n_future` future trials.
+    ///
+    /// Uses [`midpoint`](Self::midpoint) as the expected proportion and combines the binomial variance
+    /// of the future trials `p·(1 − p) / n_future` with the uncertainty of the current estimate
+    /// ([`to_variance`](Self::to_variance)). The result is clamped to `[0, 1]`.
+    ///
+    /// As `n_future` grows, the interval approaches `[low, high]` of the current one,
+    /// as only the uncertainty about the true rate remains.
+    /// `z` must be the same value the interval was calculated with.
+    #[must_use]
+    pub fn posterior_predictive(&self, n_future: FP, z: FP) -> WilsonResult {
+        let p = self.midpoint();
+        let variance = p * (1.0 - p) / n_future + self.to_variance(z);
+        let margin = z * variance.sqrt();
+        WilsonResult {
+            low: (p - margin).max(0.0),
+            high: (p + margin).min(1.0),
+        }
+    }
+
+    /// Approximate interval for the probability that two independent events both happen,
+    /// e.g. passing two stages of a pipeline. Bounds are simply multiplied.
+    ///
+    /// This ignores how the uncertainties of the two estimates combine,
+    /// so the resulting interval is conservative (wider than necessary) rather than exact.
+    #[must_use]
+    pub fn and(&self, other: &WilsonResult) -> WilsonResult {
+        WilsonResult {
+            low: self.low * other.low,
+            high: self.high * other.high,
+        }
+    }
+
+    /// Compare lower bounds for ranking, treating bounds within `tolerance` of each other as equal.
+    ///
+    /// Keeps stable sorts from reordering items whose lower bounds differ only by floating-point noise.
+    /// The relation is not transitive for chains of close values, which sorting tolerates but does not make meaningful.
+    /// NaN bounds compare by [`FP::total_cmp`].
+    ///
+    /// ```
+    /// use core::cmp::Ordering;
+    /// let a = wilson::wilson(30.0, 100.0, 1.96);
+    /// let b = wilson::wilson(300.0, 1000.0, 1.96);
+    /// assert_eq!(a.lower_bound_cmp(&b, 0.1), Ordering::Equal);
+    /// assert_eq!(a.lower_bound_cmp(&b, 0.0), Ordering::Less);
+    /// ```
+    #[must_use]
+    pub fn lower_bound_cmp(&self, other: &WilsonResult, tolerance: FP) -> core::cmp::Ordering {
+        if (self.low - other.low).abs() <= tolerance {
+            core::cmp::Ordering::Equal
+        } else {
+            self.low.total_cmp(&other.low)
+        }
+    }
+
+    /// Whether `other` lies entirely within this interval (shared ends count as contained).
+    #[must_use]
+    pub fn contains_interval(&self, other: &WilsonResult) -> bool {
+        self.low <= other.low && other.high <= self.high
+    }
+
+    /// Whether this interval lies entirely within `outer`, the reverse of [`contains_interval`](Self::contains_interval).
+    #[must_use]
+    pub fn is_contained_by(&self, outer: &WilsonResult) -> bool {
+        outer.contains_interval(self)
+    }
+
+    /// Smaller of the distances between the ends of this interval and of `outer` containing it,
+    /// `min(low − outer.low, outer.high − high)`. `Some(0)` if they touch, `None` if this interval is not contained.
+    ///
+    /// ```
+    /// let inner = wilson::WilsonResult { low: 0.3, high: 0.4 };
+    /// let outer = wilson::WilsonResult { low: 0.25, high: 0.6 };
+    /// assert!((inner.containment_margin(&outer).unwrap() - 0.05).abs() < 1e-6);
+    /// assert_eq!(outer.containment_margin(&inner), None);
+    /// ```
+    #[must_use]
+    pub fn containment_margin(&self, outer: &WilsonResult) -> Option<FP> {
+        if !self.is_contained_by(outer) {
+            return None;
+        }
+        Some((self.low - outer.low).min(outer.high - self.high))
+    }
+
+    /// Fraction of the interval above `threshold`, as a soft version of `low > threshold`:
+    /// 0 if `threshold >= high`, 1 if `threshold <= low`, and `(high − threshold) / width` in between.
+    ///
+    /// This treats all values in the interval as equally plausible, so it is a decision score, not a probability.
+    ///
+    /// ```
+    /// let r = wilson::WilsonResult { low: 0.2, high: 0.6 };
+    /// assert!((r.wilson_area_above_threshold(0.5) - 0.25).abs() < 1e-6);
+    /// assert_eq!(r.wilson_area_above_threshold(0.1), 1.0);
+    /// ```
+    #[must_use]
+    pub fn wilson_area_above_threshold(&self, threshold: FP) -> FP {
+        if threshold >= self.high {
+            0.0
+        } else if threshold <= self.low {
+            1.0
+        } else {
+            (self.high - threshold) / self.width()
+        }
+    }
+
+    /// Fraction of the interval below `threshold`, the complement of
+    /// [`wilson_area_above_threshold`](Self::wilson_area_above_threshold):
+    /// 1 if `threshold >= high`, 0 if `threshold <= low`, and `(threshold − low) / width` in between.
+    #[must_use]
+    pub fn wilson_area_below_threshold(&self, threshold: FP) -> FP {
+        if threshold >= self.high {
+            1.0
+        } else if threshold <= self.low {
+            0.0
+        } else {
+            (threshold - self.low) / self.width()
+        }
+    }
+
+    /// Interval rounded outward to `decimals` decimal places for display: `low` down and `high` up,
+    /// so the shown interval never understates the uncertainty (or collapses to a point).
+    ///
+    /// Bounds already on the rounding grid (up to floating-point noise, e.g. `0.3`) stay where they are.
+    /// For percentages with one decimal use `decimals = 3`.
+    ///
+    /// ```
+    /// let r = wilson::WilsonResult { low: 0.296, high: 0.704 }.rounded(1);
+    /// assert_eq!((r.low, r.high), (0.2, 0.8));
+    /// ```
+    #[must_use]
+    pub fn rounded(&self, decimals: u32) -> WilsonResult {
+        let scale = (10.0 as FP).powi(decimals as i32);
+        let snap = |x: FP, round: fn(FP) -> FP| {
+            let scaled = x * scale;
+            let nearest = scaled.round();
+            let grid = if (scaled - nearest).abs() <= 16.0 * FP::EPSILON * nearest.abs().max(1.0) {
+                nearest
+            } else {
+                round(scaled)
+            };
+            (grid / scale).clamp(0.0, 1.0)
+        };
+        WilsonResult {
+            low: snap(self.low, FP::floor),
+            high: snap(self.high, FP::ceil),
+        }
+    }
+
+    /// Whether the rate is confidently above `threshold`, i.e. `low > threshold`,
+    /// e.g. for the pass rate from [`flaky_test_interval`].
+    #[must_use]
+    pub fn is_reliable(&self, threshold: FP) -> bool {
+        self.low > threshold
+    }
+
+    /// Bounds converted to odds `p / (1 − p)`, e.g. `0.2` becomes `0.25` ("1 to 4").
+    ///
+    /// The conversion is monotone, so these are exact bounds for the odds, unlike the delta-method
+    /// [`wilson_log_odds_scale`]. A bound of 1 gives infinite odds.
+    ///
+    /// ```
+    /// let odds = wilson::WilsonResult { low: 0.2, high: 0.5 }.to_odds();
+    /// assert!((odds.0 - 0.25).abs() < 1e-6);
+    /// assert_eq!(odds.1, 1.0);
+    /// ```
+    #[must_use]
+    pub fn to_odds(&self) -> (FP, FP) {
+        let odds = |p: FP| if p >= 1.0 { FP::INFINITY } else { p / (1.0 - p) };
+        (odds(self.low), odds(self.high))
+    }
+
+    /// Inverse of [`to_odds`](Self::to_odds): probabilities `odds / (1 + odds)`, with infinite odds giving 1.
+    #[must_use]
+    pub fn from_odds(low_odds: FP, high_odds: FP) -> WilsonResult {
+        let probability = |odds: FP| if odds == FP::INFINITY { 1.0 } else { odds / (1.0 + odds) };
+        WilsonResult {
+            low: probability(low_odds),
+            high: probability(high_odds),
+        }
+    }
+
+    /// Natural logarithms of [`to_odds`](Self::to_odds), for reading alongside logistic regression coefficients.
+    /// Bounds of 0 and 1 give infinite log-odds.
+    #[must_use]
+    pub fn to_log_odds(&self) -> (FP, FP) {
+        let (low, high) = self.to_odds();
+        (low.ln(), high.ln())
+    }
+
+    /// Format the interval like its [`Display`](core::fmt::Display) impl into `buf` without allocating,
+    /// returning the number of bytes written.
+    ///
+    /// If `buf` is too small, it gets filled with as much of the text as fits (possibly cutting a character)
+    /// and `Err` is returned.
+    ///
+    /// ```
+    /// let mut buf = [0u8; 64];
+    /// let len = wilson::wilson(2.0, 10.0, 1.96).write_to(&mut buf).unwrap();
+    /// assert!(buf[..len].starts_with(b"0.05"));
+    /// assert!(wilson::wilson(2.0, 10.0, 1.96).write_to(&mut [0u8; 8]).is_err());
+    /// ```
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, core::fmt::Error> {
+        struct SliceWriter<'a> {
+            buf: &'a mut [u8],
+            len: usize,
+        }
+
+        impl core::fmt::Write for SliceWriter<'_> {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let free = &mut self.buf[self.len..];
+                let n = s.len().min(free.len());
+                free[..n].copy_from_slice(&s.as_bytes()[..n]);
+                self.len += n;
+                if n < s.len() {
+                    return Err(core::fmt::Error);
+                }
+                Ok(())
+            }
+        }
+
+        let mut writer = SliceWriter { buf, len: 0 };
+        core::fmt::write(&mut writer, format_args!("{}", self))?;
+        Ok(writer.len)
+    }
+}
+
+/// `low–high`, applying the precision to both bounds: `format!("{:.3}", r)` gives e.g. `0.057–0.510`.
+impl core::fmt::Display for WilsonResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(prec) = f.precision() {
+            write!(f, "{:.*}–{:.*}", prec, self.low, prec, self.high)
+        } else {
+            write!(f, "{}–{}", self.low, self.high)
+        }
+    }
+}
+
+impl From<WilsonResult> for RangeInclusive<FP> {
+    fn from(r: WilsonResult) -> Self {
+        r.low..=r.high
+    }
+}
+
+/// Converts `low..=high` back to a [`WilsonResult`].
+///
+/// Panics if the range is inverted, contains NaN or goes outside `[0, 1]`.
+impl From<RangeInclusive<FP>> for WilsonResult {
+    fn from(r: RangeInclusive<FP>) -> Self {
+        let (low, high) = r.into_inner();
+        assert!(
+            0.0 <= low && low <= high && high <= 1.0,
+            "Wilson interval range must satisfy 0 <= low <= high <= 1",
+        );
+        WilsonResult { low, high }
+    }
+}
+
+/// Both ends are inclusive, so [`RangeBounds::contains`] checks `low <= x && x <= high`.
+impl RangeBounds<FP> for WilsonResult {
+    fn start_bound(&self) -> Bound<&FP> {
+        Bound::Included(&self.low)
+    }
+
+    fn end_bound(&self) -> Bound<&FP> {
+        Bound::Included(&self.high)
+    }
+}
+
 /// Calculate upper and lower bounds of the Wilson interval.
 /// 
 /// `successes` divided by `trials` should be between `low` and `high`.
@@ -33,6 +437,18 @@ pub struct WilsonResult {
 /// You can use fractional `trials` and/or `successes`. `successes=0` or `successes=trials` should work properly.
 /// 
 /// `z=3` should appriximately correspond to 99.7% confidence, `z=2` to 95% and `z=1` to about two thirds.
+/// `z=0` collapses the interval to exactly `successes / trials` (for non-zero `trials`).
+/// Bounds are clamped to `[0, 1]`, so rounding errors cannot push them outside.
+/// With the `f32` feature, they are also widened to contain `successes / trials`, which rounding
+/// at single precision can otherwise miss (e.g. a lower bound of `3e-8` for zero successes).
+/// Huge `z` whose square overflows results in `[0, 1]`; [`try_wilson`] rejects such `z` instead.
+///
+/// For fixed `trials` and `z`, both `low` and `high` never decrease as `successes` grows, fractional steps included,
+/// so ranking by either bound is consistent. The bounds are computed in a form without cancellation to keep
+/// this true near `0` and `trials`, where rounding noise would otherwise dominate; only steps of a few
+/// units in the last place of `successes` can still be out of order.
+/// Bounds near `0` keep full relative accuracy, not just absolute, so rare-event rates such as one success
+/// in a billion trials get an upper bound correct to a few units in the last place.
 /// 
 /// ```
 /// # fn ban_user(){}
@@ -57,71 +473,2214 @@ pub struct WilsonResult {
 /// ```
 #[must_use]
 pub fn wilson(successes: FP, trials: FP, z: FP) -> WilsonResult {
+    let (low, high) = generic::wilson_bounds(successes, trials, z, cfg!(feature = "fma"));
+    let result = WilsonResult { low, high };
+    #[cfg(feature = "f32")]
+    let result = contain_estimate(result, successes, trials);
+    result
+}
+
+/// Widen `result` to contain the point estimate `successes / trials`.
+#[cfg(feature = "f32")]
+fn contain_estimate(result: WilsonResult, successes: FP, trials: FP) -> WilsonResult {
     if trials <= 0.001 {
-        return WilsonResult {
-            low: 0.0,
-            high: 1.0,
-        };
+        return result;
+    }
+    let estimate = (successes / trials).clamp(0.0, 1.0);
+    WilsonResult {
+        low: result.low.min(estimate),
+        high: result.high.max(estimate),
     }
-    let n = trials;
-    let s = successes;
-    let p = (s + 0.5 * z * z) / (n + z * z);
-    let d = z / (n + z * z) * (s * (n - s) / n + z * z / 4.0).sqrt();
-    let high = p + d;
-    let low = p - d;
-    WilsonResult { low, high }
 }
 
-#[cfg(test)]
-mod tests {
-    use approx::assert_abs_diff_eq;
+/// Wilson interval in the `center ± half_width` form, see [`wilson_parts`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WilsonParts {
+    /// Adjusted estimate `(s + z²/2) / (n + z²)`, shrunk towards 0.5 compared to `s / n`
+    pub center: FP,
+    /// Margin around `center`, not affected by clamping
+    pub half_width: FP,
+}
 
-    use super::*;
+impl WilsonParts {
+    /// Bounds `center ± half_width`, clamped to `[0, 1]` to absorb rounding errors near the edges.
+    #[must_use]
+    pub fn to_result(&self) -> WilsonResult {
+        WilsonResult {
+            low: (self.center - self.half_width).clamp(0.0, 1.0),
+            high: (self.center + self.half_width).clamp(0.0, 1.0),
+        }
+    }
+}
 
-    #[test]
-    fn it_works() {
-        {
-            let out = wilson(1.0, 2.0, 2.0);
-            assert_abs_diff_eq!(out.low, 0.09175170954, epsilon = 0.000001);
-            assert_abs_diff_eq!(out.high, 0.9082482905, epsilon = 0.000001);
+/// Calculate the Wilson interval as its unclamped adjusted center and half-width.
+///
+/// [`wilson`] is `wilson_parts(..).to_result()` up to rounding (and except for the `f32` adjustment described there).
+/// Reconstructing the margin from clamped bounds
+/// is slightly off near 0 and 1, this gives the exact values. Zero `trials` result in `0.5 ± 0.5`,
+/// as well as `z` so huge that `z²` overflows (which is also the limit of the interval for growing `z`).
+#[must_use]
+pub fn wilson_parts(successes: FP, trials: FP, z: FP) -> WilsonParts {
+    let (center, half_width) = generic::center_and_margin(successes, trials, z, cfg!(feature = "fma"));
+    WilsonParts { center, half_width }
+}
+
+/// Inputs of [`wilson`] stored for computing the interval on first use, e.g. in caches where most bounds are never read.
+///
+/// The result is memoized in a [`OnceCell`](std::cell::OnceCell), so `LazyWilson` is [`Send`] but not [`Sync`]:
+/// it can be moved to another thread, but not shared between threads by reference.
+/// To share one, compute the interval with [`get`](Self::get) and share that instead.
+///
+/// ```
+/// let lazy = wilson::LazyWilson::new(3.0, 10.0, 1.96);
+/// assert_eq!(lazy.get(), wilson::wilson(3.0, 10.0, 1.96));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LazyWilson {
+    successes: FP,
+    trials: FP,
+    z: FP,
+    // `(low, high)`, as `WilsonResult` is not `Clone`
+    result: std::cell::OnceCell<(FP, FP)>,
+}
+
+impl LazyWilson {
+    /// Store the inputs without calculating anything.
+    #[must_use]
+    pub fn new(successes: FP, trials: FP, z: FP) -> LazyWilson {
+        LazyWilson {
+            successes,
+            trials,
+            z,
+            result: std::cell::OnceCell::new(),
         }
-        {
-            let out = wilson(10.0, 20.0, 2.0);
-            assert_abs_diff_eq!(out.low, 0.2958758548, epsilon = 0.000001);
-            assert_abs_diff_eq!(out.high, 0.7041241452, epsilon = 0.000001);
+    }
+
+    /// Calculate the interval on the first call, return the stored one on later calls.
+    #[must_use]
+    pub fn get(&self) -> WilsonResult {
+        let &(low, high) = self.result.get_or_init(|| {
+            let out = wilson(self.successes, self.trials, self.z);
+            (out.low, out.high)
+        });
+        WilsonResult { low, high }
+    }
+
+    /// Whether the interval has been calculated already.
+    #[must_use]
+    pub fn is_computed(&self) -> bool {
+        self.result.get().is_some()
+    }
+}
+
+/// Intermediate values of the [`wilson`] calculation, see [`wilson_diagnostic`].
+///
+/// `Display` prints them as a table, one value per line.
+#[derive(Debug, PartialEq)]
+pub struct WilsonDiagnostic {
+    /// Number of trials as passed in
+    pub n: FP,
+    /// Number of successes as passed in
+    pub s: FP,
+    /// Adjusted proportion `(s + z²/2) / (n + z²)`, the center of the interval
+    pub p_adjusted: FP,
+    /// Margin around `p_adjusted` before clamping
+    pub half_width: FP,
+    /// Same as returned by [`wilson`]
+    pub result: WilsonResult,
+}
+
+impl core::fmt::Display for WilsonDiagnostic {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "n           {}", self.n)?;
+        writeln!(f, "s           {}", self.s)?;
+        writeln!(f, "p_adjusted  {}", self.p_adjusted)?;
+        writeln!(f, "half_width  {}", self.half_width)?;
+        writeln!(f, "low         {}", self.result.low)?;
+        writeln!(f, "high        {}", self.result.high)
+    }
+}
+
+/// Calculate the Wilson interval along with its intermediate values, for debugging unexpected results.
+///
+/// `result` is exactly what [`wilson`] returns. It agrees with `p_adjusted ± half_width` up to rounding
+/// (and clamping, and the `f32` adjustment), as [`wilson`] rearranges the bounds to avoid cancellation;
+/// degenerate input shows up as `0.5 ± 0.5`, see [`wilson_parts`].
+///
+/// ```
+/// let diagnostic = wilson::wilson_diagnostic(0.0, 3.0, 1.96);
+/// assert!(diagnostic.p_adjusted - diagnostic.half_width < 1e-9);
+/// let table = format!("{diagnostic}");
+/// assert!(table.starts_with("n           3\ns           0\np_adjusted  "));
+/// assert!(table.ends_with(&format!("low         0\nhigh        {}\n", diagnostic.result.high)));
+/// ```
+#[must_use]
+pub fn wilson_diagnostic(successes: FP, trials: FP, z: FP) -> WilsonDiagnostic {
+    let parts = wilson_parts(successes, trials, z);
+    WilsonDiagnostic {
+        n: trials,
+        s: successes,
+        p_adjusted: parts.center,
+        half_width: parts.half_width,
+        result: wilson(successes, trials, z),
+    }
+}
+
+/// Mode (MAP estimate) of `Beta(s + z²/2, n − s + z²/2)`, the posterior implicit in the Wilson interval:
+/// `(s + z²/2 − 1) / (n + z² − 2)`.
+///
+/// Three point estimates come with the interval:
+/// * the mode, the single most likely rate, closest to the naive `s / n` (equal to it at `z = √2`);
+/// * the mean [`WilsonParts::center`], minimizing squared error, best for averaging or expected values;
+/// * the median, halfway between the two for moderate counts, robust to the skew near 0 and 1.
+///
+/// Near the edges the posterior density peaks at the boundary: if `s + z²/2 ≤ 1` the mode is `0`,
+/// if `n − s + z²/2 ≤ 1` it is `1`. If both hold, the density is U-shaped or flat
+/// and `0.5` is returned, as it is for zero `trials`.
+///
+/// For integer counts and `z ≥ 1` the mode lies within the [`wilson`] interval. With smaller `z`
+/// the mode is pushed away from `0.5` beyond `s / n` and can fall outside the narrow interval.
+///
+/// ```
+/// assert_eq!(wilson::wilson_mode(3.0, 10.0, 2.0_f64.sqrt() as wilson::FP), 0.3);
+/// assert_eq!(wilson::wilson_mode(0.0, 10.0, 1.0), 0.0);
+/// ```
+#[must_use]
+pub fn wilson_mode(successes: FP, trials: FP, z: FP) -> FP {
+    if trials <= 0.001 {
+        return 0.5;
+    }
+    let alpha = successes + 0.5 * z * z;
+    let beta = trials - successes + 0.5 * z * z;
+    match (alpha > 1.0, beta > 1.0) {
+        (true, true) => ((alpha - 1.0) / (alpha + beta - 2.0)).clamp(0.0, 1.0),
+        (false, true) => 0.0,
+        (true, false) => 1.0,
+        (false, false) => 0.5,
+    }
+}
+
+/// Mean of `Beta(s + z²/2, n − s + z²/2)`, the posterior implicit in the Wilson interval (see [`wilson_mode`]):
+/// `(s + z²/2) / (n + z²)`, the same as [`WilsonParts::center`] and the [`midpoint`](WilsonResult::midpoint)
+/// of the interval.
+///
+/// So the Wilson midpoint is the estimate after adding `z²/2` pseudo-successes and pseudo-failures.
+/// Zero `trials` result in 0.5.
+#[must_use]
+pub fn wilson_posterior_mean(successes: FP, trials: FP, z: FP) -> FP {
+    wilson_parts(successes, trials, z).center
+}
+
+/// Variance of the same posterior as [`wilson_posterior_mean`]: `αβ / ((α + β)²·(α + β + 1))`
+/// with `α = s + z²/2` and `β = n − s + z²/2`.
+///
+/// Shrinks roughly as `1 / trials`. Returns NaN without trials and with `z = 0`, as there is no posterior.
+///
+/// ```
+/// let variance = wilson::wilson_posterior_variance(30.0, 100.0, 1.96);
+/// assert!((variance.sqrt() - 0.0452).abs() < 1e-3);
+/// ```
+#[must_use]
+pub fn wilson_posterior_variance(successes: FP, trials: FP, z: FP) -> FP {
+    let alpha = successes + 0.5 * z * z;
+    let beta = trials - successes + 0.5 * z * z;
+    let total = alpha + beta;
+    alpha * beta / (total * total * (total + 1.0))
+}
+
+/// Exact `z` value for two-sided 95% confidence: the standard normal quantile at 0.975.
+///
+/// Textbooks often round it to `1.96`, which makes results differ from R's `prop.test`
+/// or SciPy in the fourth or fifth decimal. [`wilson_confidence`] uses the exact value.
+#[allow(clippy::excessive_precision)]
+pub const Z_95_EXACT: FP = 1.959_963_984_540_054;
+
+/// Convert two-sided confidence level (e.g. `0.95`) to the corresponding `z` value (e.g. `1.96`).
+///
+/// For one-sided bounds such as [`wilson_lower_bound`], use [`one_sided_confidence_to_z`] instead.
+///
+/// The result is exact, not rounded like in tables, see [`Z_95_EXACT`].
+/// Returns NaN if `confidence` is outside `[0, 1]`.
+#[must_use]
+pub fn z_from_confidence(confidence: FP) -> FP {
+    normal::normal_quantile(0.5 + 0.5 * confidence)
+}
+
+/// Convert **one-sided** confidence level to `z`: the standard normal quantile at `confidence` itself,
+/// e.g. `0.95` gives `1.645`.
+///
+/// This is the `z` that lower-bound ranking ([`wilson_lower_bound`] and friends) needs
+/// for "the true rate is above this bound with 95% confidence".
+/// [`z_from_confidence`] is for two-sided intervals and uses the quantile at `(1 + confidence) / 2`,
+/// giving `1.96` for `0.95`; passing its result to a one-sided bound makes the bound
+/// more conservative than intended (97.5% instead of 95%). Mixing the two up is a frequent bug.
+///
+/// Returns NaN if `confidence` is outside `[0, 1]`.
+///
+/// ```
+/// let z = wilson::one_sided_confidence_to_z(0.95);
+/// assert!((z - 1.644854).abs() < 1e-5);
+/// assert!((wilson::z_from_confidence(0.90) - z).abs() < 1e-5);
+/// ```
+#[must_use]
+pub fn one_sided_confidence_to_z(confidence: FP) -> FP {
+    normal::normal_quantile(confidence)
+}
+
+/// Convert `z` value to the corresponding two-sided confidence level, inverse of [`z_from_confidence`].
+#[must_use]
+pub fn confidence_from_z(z: FP) -> FP {
+    1.0 - 2.0 * normal::normal_sf(z)
+}
+
+/// Like [`wilson`], but specifying two-sided confidence level (e.g. `0.95`) instead of `z`.
+///
+/// Matches R's `prop.test(successes, trials, correct = FALSE)`:
+///
+/// ```
+/// let out = wilson::wilson_confidence(10.0, 20.0, 0.95);
+/// assert!((out.low - 0.2992980).abs() < 1e-7);
+/// assert!((out.high - 0.7007020).abs() < 1e-7);
+/// ```
+#[must_use]
+pub fn wilson_confidence(successes: FP, trials: FP, confidence: FP) -> WilsonResult {
+    wilson_with_quantile(successes, trials, confidence, z_from_confidence)
+}
+
+/// Like [`wilson_confidence`], but with custom function mapping confidence level to effective `z`.
+///
+/// This allows using other distributions than the normal one, e.g. Student's t-distribution for small samples.
+///
+/// ```
+/// let out = wilson::wilson_with_quantile(2.0, 20.0, 0.95, |_| 2.0);
+/// assert_eq!(out, wilson::wilson(2.0, 20.0, 2.0));
+/// ```
+#[must_use]
+pub fn wilson_with_quantile(successes: FP, trials: FP, confidence: FP, quantile: impl Fn(FP) -> FP) -> WilsonResult {
+    wilson(successes, trials, quantile(confidence))
+}
+
+/// How a [`LabeledInterval`] was calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntervalMethod {
+    /// Wilson score interval, see [`wilson`]
+    Wilson,
+    /// Wilson score interval with `z` from [`calibrated_z`], so that the exact coverage reaches the confidence level
+    /// for every true rate. Needs a confidence level and whole `trials`.
+    CalibratedWilson,
+}
+
+/// Interval together with the information how it was made, so stored or logged intervals are self-describing.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LabeledInterval {
+    /// The interval itself
+    pub result: WilsonResult,
+    /// Two-sided confidence level the interval was calculated for
+    pub confidence: FP,
+    /// Method used to calculate the interval
+    pub method: IntervalMethod,
+}
+
+impl LabeledInterval {
+    /// Calculate the Wilson interval for the given confidence level and label it accordingly.
+    #[must_use]
+    pub fn wilson(successes: FP, trials: FP, confidence: FP) -> LabeledInterval {
+        LabeledInterval {
+            result: wilson_confidence(successes, trials, confidence),
+            confidence,
+            method: IntervalMethod::Wilson,
         }
-        {
-            let out = wilson(2.0, 20.0, 2.0);
-            assert_abs_diff_eq!(out.low, 0.02722332891, epsilon = 0.000001);
-            assert_abs_diff_eq!(out.high, 0.3061100044, epsilon = 0.000001);
+    }
+}
+
+/// Configuration error reported by [`WilsonBuilder::try_build`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// Neither `z` nor confidence level was set
+    MissingLevel,
+    /// Both `z` and confidence level were set, so it is unclear which one to use
+    ConflictingLevels,
+    /// Confidence level is not strictly between 0 and 1
+    InvalidConfidence,
+    /// The method calibrates to a confidence level, but a raw `z` was set
+    NeedsConfidence,
+    /// The method needs a whole number of trials
+    FractionalTrials,
+    /// Counts or `z` are rejected by [`try_wilson`]
+    Invalid(WilsonError),
+}
+
+impl core::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BuildError::MissingLevel => f.write_str("neither z nor confidence level is set"),
+            BuildError::ConflictingLevels => f.write_str("both z and confidence level are set"),
+            BuildError::InvalidConfidence => f.write_str("confidence level is not between 0 and 1"),
+            BuildError::NeedsConfidence => f.write_str("method needs a confidence level, not z"),
+            BuildError::FractionalTrials => f.write_str("method needs a whole number of trials"),
+            BuildError::Invalid(e) => write!(f, "invalid input: {}", e),
         }
-        {
-            let out = wilson(2.0, 20.0, 3.0);
-            assert_abs_diff_eq!(out.low, 0.01595229175, epsilon = 0.000001);
-            assert_abs_diff_eq!(out.high, 0.4323235703, epsilon = 0.000001);
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Step-by-step configuration of a [`LabeledInterval`], validated as a whole by [`try_build`](Self::try_build).
+///
+/// The interval width is set either as a two-sided confidence level or as a raw `z`, but not both.
+/// [`IntervalMethod::CalibratedWilson`] accepts only the confidence level.
+///
+/// ```
+/// use wilson::{BuildError, IntervalMethod, WilsonBuilder};
+/// let out = WilsonBuilder::new(2.0, 20.0).confidence(0.95).method(IntervalMethod::Wilson).try_build().unwrap();
+/// assert_eq!(out.result, wilson::wilson_confidence(2.0, 20.0, 0.95));
+/// let conflict = WilsonBuilder::new(2.0, 20.0).confidence(0.95).z(1.96).try_build();
+/// assert_eq!(conflict, Err(BuildError::ConflictingLevels));
+/// let raw_z = WilsonBuilder::new(2.0, 20.0).z(1.96).method(IntervalMethod::CalibratedWilson).try_build();
+/// assert_eq!(raw_z, Err(BuildError::NeedsConfidence));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WilsonBuilder {
+    successes: FP,
+    trials: FP,
+    z: Option<FP>,
+    confidence: Option<FP>,
+    method: IntervalMethod,
+}
+
+impl WilsonBuilder {
+    /// Start configuring an interval for the given counts, using [`IntervalMethod::Wilson`].
+    #[must_use]
+    pub fn new(successes: FP, trials: FP) -> WilsonBuilder {
+        WilsonBuilder {
+            successes,
+            trials,
+            z: None,
+            confidence: None,
+            method: IntervalMethod::Wilson,
         }
-        {
-            let out = wilson(20.0, 20.0, 2.0);
-            assert_abs_diff_eq!(out.low, 0.8333333333, epsilon = 0.000001);
-            assert_abs_diff_eq!(out.high, 1.0, epsilon = 0.000001);
-        }  {
-            let out = wilson(0.0, 20.0, 2.0);
-            assert_abs_diff_eq!(out.low, 0.0, epsilon = 0.000001);
-            assert_abs_diff_eq!(out.high, 0.1666666667, epsilon = 0.000001);
+    }
+
+    /// Set the raw `z` value. The label gets the corresponding confidence level, see [`confidence_from_z`].
+    #[must_use]
+    pub fn z(mut self, z: FP) -> WilsonBuilder {
+        self.z = Some(z);
+        self
+    }
+
+    /// Set the two-sided confidence level, e.g. `0.95`.
+    #[must_use]
+    pub fn confidence(mut self, confidence: FP) -> WilsonBuilder {
+        self.confidence = Some(confidence);
+        self
+    }
+
+    /// Set the method used to calculate the interval.
+    #[must_use]
+    pub fn method(mut self, method: IntervalMethod) -> WilsonBuilder {
+        self.method = method;
+        self
+    }
+
+    /// Validate the configuration and calculate the interval.
+    pub fn try_build(&self) -> Result<LabeledInterval, BuildError> {
+        let (z, confidence) = match (self.z, self.confidence) {
+            (None, None) => return Err(BuildError::MissingLevel),
+            (Some(_), Some(_)) => return Err(BuildError::ConflictingLevels),
+            (Some(z), None) => (z, confidence_from_z(z)),
+            (None, Some(confidence)) => {
+                if !(confidence > 0.0 && confidence < 1.0) {
+                    return Err(BuildError::InvalidConfidence);
+                }
+                (z_from_confidence(confidence), confidence)
+            }
+        };
+        let result = try_wilson(self.successes, self.trials, z).map_err(BuildError::Invalid)?;
+        let result = match self.method {
+            IntervalMethod::Wilson => result,
+            IntervalMethod::CalibratedWilson => {
+                if self.z.is_some() {
+                    return Err(BuildError::NeedsConfidence);
+                }
+                if self.trials.fract() != 0.0 {
+                    return Err(BuildError::FractionalTrials);
+                }
+                wilson(self.successes, self.trials, calibrated_z(self.trials as u64, confidence))
+            }
+        };
+        Ok(LabeledInterval {
+            result,
+            confidence,
+            method: self.method,
+        })
+    }
+}
+
+/// Find the largest `z` for which the interval for the given counts is not wider than `target_width`.
+///
+/// Answers "what confidence can I claim if the interval must be this narrow?".
+/// Returns infinity if any `z` will do (`target_width >= 1`) and `None` if none will,
+/// i.e. for non-positive `target_width` or zero `trials`.
+/// See [`confidence_for_width`] for the corresponding confidence level.
+#[must_use]
+pub fn z_for_width(successes: FP, trials: FP, target_width: FP) -> Option<FP> {
+    if target_width.is_nan() || target_width <= 0.0 {
+        return None;
+    }
+    if target_width >= 1.0 {
+        return Some(FP::INFINITY);
+    }
+    if trials <= 0.001 {
+        return None;
+    }
+    let width = |z: FP| wilson(successes, trials, z).width();
+    let mut lo = 0.0;
+    let mut hi = 1.0;
+    while width(hi) <= target_width {
+        lo = hi;
+        hi *= 2.0;
+        if !hi.is_finite() {
+            return Some(FP::INFINITY);
+        }
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if width(mid) <= target_width {
+            lo = mid;
+        } else {
+            hi = mid;
         }
     }
+    Some(lo)
+}
 
-    #[test]
-    fn degenerate() {
-        let out = wilson(0.0, 0.0, 2.0);
-        assert_abs_diff_eq!(out.low, 0.0, epsilon = 0.000001);
-        assert_abs_diff_eq!(out.high, 1.0, epsilon = 0.000001);
+/// Like [`z_for_width`], but returns two-sided confidence level instead of `z`.
+#[must_use]
+pub fn confidence_for_width(successes: FP, trials: FP, target_width: FP) -> Option<FP> {
+    z_for_width(successes, trials, target_width).map(confidence_from_z)
+}
+
+/// Highest two-sided confidence level at which the lower bound still exceeds `threshold`:
+/// "how confident can I be that the true rate is above 30%?".
+///
+/// The lower bound shrinks as the confidence grows, so this is the level at which it equals `threshold`,
+/// found by bisection over `z` and converted with [`confidence_from_z`].
+/// Returns `0` if even the point estimate `s / n` (`z = 0`) does not exceed `threshold`,
+/// and `1` if every confidence level does, i.e. for negative `threshold`
+/// or zero `threshold` with some successes.
+///
+/// ```
+/// let c = wilson::wilson_confidence_required(40.0, 100.0, 0.3);
+/// assert!(c > 0.95 && c < 0.99);
+/// ```
+#[must_use]
+pub fn wilson_confidence_required(successes: FP, trials: FP, threshold: FP) -> FP {
+    if threshold < 0.0 || (threshold == 0.0 && successes > 0.0 && trials > 0.001) {
+        return 1.0;
+    }
+    let lower = |z: FP| wilson_lower_bound(successes, trials, z);
+    if threshold.is_nan() || lower(0.0) <= threshold {
+        return 0.0;
+    }
+    let mut lo = 0.0;
+    let mut hi = 1.0;
+    while lower(hi) > threshold {
+        lo = hi;
+        hi *= 2.0;
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if lower(mid) > threshold {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
     }
+    confidence_from_z(lo)
+}
 
-    #[test]
-    fn degenerate2() {
-        let out = wilson(0.005, 0.01, 2.0);
-        assert_abs_diff_eq!(out.low, 0.0006238305611, epsilon = 0.000001);
-        assert_abs_diff_eq!(out.high, 0.9993761694, epsilon = 0.000001);
+/// Bisection for the proportion `s / n` at which `bound` of the interval reaches `target`,
+/// given that `bound` grows with the proportion.
+fn invert_bound(target: FP, trials: FP, z: FP, bound: impl Fn(&WilsonResult) -> FP) -> FP {
+    let at = |p: FP| bound(&wilson(p * trials, trials, z));
+    if target.is_nan() || target < at(0.0) || target > at(1.0) {
+        return FP::NAN;
+    }
+    let mut lo: FP = 0.0;
+    let mut hi: FP = 1.0;
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if at(mid) < target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    if at(lo) == target {
+        lo
+    } else {
+        hi
+    }
+}
+
+/// Observed proportion `s / n` at which the lower bound for `trials` trials equals `lower_bound`:
+/// "at what success rate would my ranking key be exactly this?", e.g. for calibrating decision thresholds.
+///
+/// Inverts [`wilson_lower_bound`] by bisection, as the lower bound grows with the proportion.
+/// Returns NaN if no proportion gives `lower_bound`, i.e. it is above the lower bound for all successes
+/// (or outside of `[0, 1]`). Zero `trials` give the `[0, 1]` interval, so only `lower_bound = 0` has an answer.
+///
+/// ```
+/// let low = wilson::wilson_lower_bound(30.0, 100.0, 1.96);
+/// assert!((wilson::wilson_invert_lower(low, 100.0, 1.96) - 0.3).abs() < 1e-6);
+/// ```
+#[must_use]
+pub fn wilson_invert_lower(lower_bound: FP, trials: FP, z: FP) -> FP {
+    invert_bound(lower_bound, trials, z, |r| r.low)
+}
+
+/// Like [`wilson_invert_lower`], the observed proportion at which the upper bound equals `upper_bound`.
+///
+/// Returns NaN for `upper_bound` below the upper bound of zero successes, `z² / (n + z²)`.
+#[must_use]
+pub fn wilson_invert_upper(upper_bound: FP, trials: FP, z: FP) -> FP {
+    invert_bound(upper_bound, trials, z, |r| r.high)
+}
+
+/// Information gained by going from interval `a` to a narrower interval `b`, in bits.
+///
+/// Difference of [`WilsonResult::wilson_information`] values, negative if `b` is wider than `a`.
+#[must_use]
+pub fn wilson_relative_information(a: &WilsonResult, b: &WilsonResult) -> FP {
+    b.wilson_information() - a.wilson_information()
+}
+
+/// Score test statistic `(s/n − p0) / sqrt(p0·(1 − p0) / n)` for the hypothesis that the true rate is `p0`.
+///
+/// This is the z-score the Wilson interval is built from: `p0` lies within `wilson(s, n, z)`
+/// exactly when the statistic is between `−z` and `z`.
+/// For `p0` of 0 or 1 the standard error is zero, so any deviation gives `±∞` and no deviation gives 0.
+/// Zero `trials` result in NaN.
+///
+/// ```
+/// // 60 out of 100 against a fair coin: two standard errors above
+/// assert!((wilson::score_statistic(60.0, 100.0, 0.5) - 2.0).abs() < 1e-6);
+/// ```
+#[must_use]
+pub fn score_statistic(successes: FP, trials: FP, p0: FP) -> FP {
+    let deviation = successes / trials - p0;
+    if deviation == 0.0 {
+        return 0.0;
+    }
+    deviation / (p0 * (1.0 - p0) / trials).sqrt()
+}
+
+/// How surprising `new_successes` out of `new_trials` are given an earlier interval `prior`,
+/// from 0 (at the middle of `prior`) to 1 (outside of it).
+///
+/// The score is `|above − below|`, where `above` and `below` are the fractions of `prior` above and below
+/// the new rate, see [`WilsonResult::wilson_area_above_threshold`]. It grows linearly from the midpoint
+/// towards the ends. Like the areas, it treats all values in the interval as equally plausible.
+/// Zero `new_trials` result in 0, as nothing was observed.
+///
+/// ```
+/// let prior = wilson::wilson(30.0, 100.0, 1.96);
+/// assert!(wilson::wilson_surprise(&prior, 3.0, 10.0) < 0.1);
+/// assert_eq!(wilson::wilson_surprise(&prior, 9.0, 10.0), 1.0);
+/// ```
+#[must_use]
+pub fn wilson_surprise(prior: &WilsonResult, new_successes: FP, new_trials: FP) -> FP {
+    if new_trials <= 0.0 {
+        return 0.0;
+    }
+    let observed = new_successes / new_trials;
+    (2.0 * prior.wilson_area_above_threshold(observed) - 1.0).abs().clamp(0.0, 1.0)
+}
+
+/// Signed distance of `observed_p` from the middle of `prior`, in approximate standard errors
+/// (see [`WilsonResult::to_standard_error`]); `prior` is assumed to be a 95% interval (`z = 1.96`).
+///
+/// Values beyond ±1.96 are outside `prior`, roughly. Positive means the observation is higher.
+#[must_use]
+pub fn wilson_z_score(prior: &WilsonResult, observed_p: FP) -> FP {
+    (observed_p - prior.midpoint()) / prior.to_standard_error(1.96)
+}
+
+/// Asymmetry of an interval around the point `estimate` (usually `successes / trials`):
+/// `(high − estimate) − (estimate − low)`.
+///
+/// Positive values mean the upper tail is wider, as for Wilson intervals of proportions near 0.
+/// Large asymmetry signals that a symmetric normal-approximation (Wald) interval would be poor.
+/// The estimate has to be passed explicitly: around its own [`midpoint`](WilsonResult::midpoint)
+/// every interval is symmetric by definition.
+///
+/// ```
+/// let out = wilson::wilson(1.0, 100.0, 1.96);
+/// assert!(wilson::wilson_asymmetry(&out, 0.01) > 0.02);
+/// ```
+#[must_use]
+pub fn wilson_asymmetry(result: &WilsonResult, estimate: FP) -> FP {
+    (result.high - estimate) - (estimate - result.low)
+}
+
+/// [`wilson_asymmetry`] divided by the interval width, from `-1` to `1` for estimates within the interval:
+/// `0` is symmetric and `±1` means the estimate is at one of the ends.
+/// Zero-width intervals give `0`.
+#[must_use]
+pub fn wilson_asymmetry_ratio(result: &WilsonResult, estimate: FP) -> FP {
+    let width = result.width();
+    if width == 0.0 {
+        return 0.0;
+    }
+    wilson_asymmetry(result, estimate) / width
+}
+
+/// Interval for a 1–5 star rating histogram (`histogram[0]` is the number of one-star reviews).
+///
+/// Star `k` counts as a success of weight `(k − 1) / 4`: five stars are a full success, one star a full failure
+/// and three stars half of each. The interval is calculated for the resulting fractional successes
+/// out of all reviews, so it is on the `[0, 1]` scale; map it back to stars with `1 + 4·x`.
+/// An empty histogram gives the `[0, 1]` interval.
+///
+/// ```
+/// let out = wilson::wilson_rating(&[0, 0, 10, 0, 0], 1.96);
+/// assert_eq!(out, wilson::wilson(5.0, 10.0, 1.96));
+/// ```
+#[must_use]
+pub fn wilson_rating(histogram: &[u64; 5], z: FP) -> WilsonResult {
+    let trials: FP = histogram.iter().map(|&count| count as FP).sum();
+    let successes: FP = histogram
+        .iter()
+        .enumerate()
+        .map(|(k, &count)| count as FP * k as FP / 4.0)
+        .sum();
+    wilson(successes, trials, z)
+}
+
+/// Lower bound of [`wilson_rating`], a sort key for products by their star histograms.
+#[must_use]
+pub fn rating_lower_bound(histogram: &[u64; 5], z: FP) -> FP {
+    wilson_rating(histogram, z).low
+}
+
+/// Wilson interval for raw per-trial results: `true` values are successes and the slice length is the number of trials.
+///
+/// An empty slice gives the `[0, 1]` interval.
+///
+/// ```
+/// let out = wilson::wilson_from_outcomes(&[true, false, false, true], 1.96);
+/// assert_eq!(out, wilson::wilson(2.0, 4.0, 1.96));
+/// ```
+#[must_use]
+pub fn wilson_from_outcomes(outcomes: &[bool], z: FP) -> WilsonResult {
+    let successes = outcomes.iter().filter(|&&outcome| outcome).count();
+    wilson(successes as FP, outcomes.len() as FP, z)
+}
+
+/// Lower bound of the Wilson interval, the classic key for ranking items by rating.
+///
+/// For a one-sided bound at a given confidence, pass the one-sided `z`, e.g. `1.645` for 95%.
+#[must_use]
+pub fn wilson_lower_bound(successes: FP, trials: FP, z: FP) -> FP {
+    wilson(successes, trials, z).low
+}
+
+/// Like [`wilson_lower_bound`], but shrinks towards `prior_rate` instead of 0 for items with little data.
+///
+/// Adds `prior_weight · prior_rate` pseudo-successes and `prior_weight` pseudo-trials before calculating the bound,
+/// so brand new items rank near `prior_rate` (minus the uncertainty of `prior_weight` trials).
+/// With `prior_weight = 0` it is the same as [`wilson_lower_bound`].
+#[must_use]
+pub fn wilson_lower_bound_prior(successes: FP, trials: FP, z: FP, prior_rate: FP, prior_weight: FP) -> FP {
+    wilson_lower_bound(successes + prior_weight * prior_rate, trials + prior_weight, z)
+}
+
+/// Wilson interval after adding `prior_successes` out of `prior_trials` pseudo-counts to the observed ones,
+/// e.g. `2` out of `100` to pull items with little data towards a baseline rate of 2%.
+///
+/// This is ad-hoc smoothing, not a Bayesian posterior interval: the pseudo-counts are treated
+/// exactly like observed trials, so they also make the interval narrower, as if that much data had been seen.
+/// Choose `prior_trials` as the amount of real data the prior is worth.
+/// With zero pseudo-counts the result is bit-identical to [`wilson`]. See [`wilson_lower_bound_prior`]
+/// for the lower bound with the prior given as a rate and a weight.
+///
+/// Panics if `prior_successes` is negative or exceeds `prior_trials`.
+///
+/// ```
+/// let fresh = wilson::wilson_smoothed(0.0, 0.0, 1.96, 2.0, 100.0);
+/// assert!(fresh.low < 0.02 && 0.02 < fresh.high && fresh.high < 0.1);
+/// ```
+#[must_use]
+pub fn wilson_smoothed(successes: FP, trials: FP, z: FP, prior_successes: FP, prior_trials: FP) -> WilsonResult {
+    assert!(
+        0.0 <= prior_successes && prior_successes <= prior_trials,
+        "prior successes must be between 0 and prior trials",
+    );
+    wilson(successes + prior_successes, trials + prior_trials, z)
+}
+
+/// Wilson interval for `(successes, trials, weight)` observations pooled with weights,
+/// e.g. recency weights `exp(-lambda · age)` to make old data count less.
+///
+/// Calculates [`wilson`] for effective counts `Σ weight·successes` and `Σ weight·trials`.
+/// Note that small weights also make the interval wider, as the data counts as fewer trials.
+#[must_use]
+pub fn wilson_score_decay(observations: &[(FP, FP, FP)], z: FP) -> WilsonResult {
+    let (s, n) = observations
+        .iter()
+        .fold((0.0, 0.0), |(s, n), &(os, on, w)| (s + w * os, n + w * on));
+    wilson(s, n, z)
+}
+
+/// Lower bounds of a stored score before and after switching from `old_z` to `new_z`, as `(old, new)`.
+///
+/// Helps migration scripts recompute persisted [`wilson_lower_bound`] scores when the confidence level changes,
+/// see also [`wilson_score_changed`].
+#[must_use]
+pub fn wilson_score_migration_map(old_successes: FP, old_trials: FP, old_z: FP, new_z: FP) -> (FP, FP) {
+    (
+        wilson_lower_bound(old_successes, old_trials, old_z),
+        wilson_lower_bound(old_successes, old_trials, new_z),
+    )
+}
+
+/// Whether the stored lower bound score moves by more than `tolerance` when switching from `old_z` to `new_z`,
+/// so migrations can update only rows that changed significantly.
+///
+/// ```
+/// // Rows with lots of trials barely move when going from 1.96 to 2.0
+/// assert!(!wilson::wilson_score_changed(5000.0, 10000.0, 1.96, 2.0, 0.001));
+/// assert!(wilson::wilson_score_changed(5.0, 10.0, 1.96, 2.0, 0.001));
+/// ```
+#[must_use]
+pub fn wilson_score_changed(s: FP, n: FP, old_z: FP, new_z: FP, tolerance: FP) -> bool {
+    let (old, new) = wilson_score_migration_map(s, n, old_z, new_z);
+    (old - new).abs() > tolerance
+}
+
+/// Reason why [`try_wilson`] or other checked function of this crate rejected its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WilsonError {
+    /// Some of the inputs is NaN or infinite.
+    NonFinite,
+    /// `successes` or `trials` is negative.
+    Negative,
+    /// `successes` is greater than `trials`.
+    SuccessesExceedTrials,
+    /// A proportion, probability or confidence level is outside of its valid range.
+    OutOfRange,
+    /// `z` is negative or so huge that `z²` overflows.
+    InvalidZ,
+    /// Sensitivity plus specificity does not exceed 1, so the classifier carries no information.
+    UninformativeTest,
+    /// Fewer than two groups with trials to compare.
+    TooFewGroups,
+}
+
+impl core::fmt::Display for WilsonError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WilsonError::NonFinite => f.write_str("input is NaN or infinite"),
+            WilsonError::Negative => f.write_str("successes or trials is negative"),
+            WilsonError::SuccessesExceedTrials => f.write_str("successes exceed trials"),
+            WilsonError::OutOfRange => f.write_str("parameter is out of its valid range"),
+            WilsonError::InvalidZ => f.write_str("z is negative or too large"),
+            WilsonError::UninformativeTest => f.write_str("sensitivity plus specificity does not exceed 1"),
+            WilsonError::TooFewGroups => f.write_str("fewer than two groups with trials"),
+        }
+    }
+}
+
+impl std::error::Error for WilsonError {}
+
+/// Checked version of [`wilson`] that reports invalid input instead of producing garbage or panicking.
+pub fn try_wilson(successes: FP, trials: FP, z: FP) -> Result<WilsonResult, WilsonError> {
+    if !successes.is_finite() || !trials.is_finite() || !z.is_finite() {
+        return Err(WilsonError::NonFinite);
+    }
+    if successes < 0.0 || trials < 0.0 {
+        return Err(WilsonError::Negative);
+    }
+    if successes > trials {
+        return Err(WilsonError::SuccessesExceedTrials);
+    }
+    if z < 0.0 || (z * z).is_infinite() {
+        return Err(WilsonError::InvalidZ);
+    }
+    Ok(wilson(successes, trials, z))
+}
+
+/// Apply [`try_wilson`] to each `(successes, trials)` pair, keeping errors per row.
+///
+/// ```
+/// let out = wilson::try_wilson_batch(&[(1.0, 2.0), (3.0, 2.0)], 2.0);
+/// assert!(out[0].is_ok());
+/// assert_eq!(out[1], Err(wilson::WilsonError::SuccessesExceedTrials));
+/// ```
+#[must_use]
+pub fn try_wilson_batch(data: &[(FP, FP)], z: FP) -> Vec<Result<WilsonResult, WilsonError>> {
+    data.iter().map(|&(s, n)| try_wilson(s, n, z)).collect()
+}
+
+/// Interval for the true prevalence when successes are detected by an imperfect classifier
+/// with known `sensitivity` and `specificity` (Rogan–Gladen correction).
+///
+/// The observed rate is biased: false positives inflate it and false negatives deflate it.
+/// Both bounds of the [`try_wilson`] interval for the observed rate are mapped through the correction
+/// `(p + specificity − 1) / (sensitivity + specificity − 1)` and clamped to `[0, 1]`,
+/// as observed rates below the false positive rate `1 − specificity` (or above `sensitivity`) fall outside.
+///
+/// Fails with [`WilsonError::OutOfRange`] if `sensitivity` or `specificity` is outside `[0, 1]`
+/// and with [`WilsonError::UninformativeTest`] if their sum does not exceed 1.
+///
+/// ```
+/// // Half of the true cases are missed, so the true rate is about twice the observed one
+/// let raw = wilson::wilson(30.0, 100.0, 1.96);
+/// let out = wilson::wilson_corrected(30.0, 100.0, 0.5, 1.0, 1.96).unwrap();
+/// assert_eq!((out.low, out.high), (2.0 * raw.low, 2.0 * raw.high));
+/// ```
+pub fn wilson_corrected(
+    observed_successes: FP,
+    trials: FP,
+    sensitivity: FP,
+    specificity: FP,
+    z: FP,
+) -> Result<WilsonResult, WilsonError> {
+    if !sensitivity.is_finite() || !specificity.is_finite() {
+        return Err(WilsonError::NonFinite);
+    }
+    if !(0.0..=1.0).contains(&sensitivity) || !(0.0..=1.0).contains(&specificity) {
+        return Err(WilsonError::OutOfRange);
+    }
+    let informativeness = sensitivity + specificity - 1.0;
+    if informativeness <= 0.0 {
+        return Err(WilsonError::UninformativeTest);
+    }
+    let observed = try_wilson(observed_successes, trials, z)?;
+    let false_positive_rate = 1.0 - specificity;
+    let correct = |p: FP| ((p - false_positive_rate) / informativeness).clamp(0.0, 1.0);
+    Ok(WilsonResult {
+        low: correct(observed.low),
+        high: correct(observed.high),
+    })
+}
+
+/// Like [`wilson`], but swaps `successes` and `trials` if they look like being passed in the wrong order,
+/// i.e. when both are non-negative and `successes > trials`.
+///
+/// This masks input errors instead of reporting them, so use it deliberately, e.g. for messy integrations
+/// you cannot fix. See [`wilson_auto_with`] for getting notified about the swaps.
+#[must_use]
+pub fn wilson_auto(successes: FP, trials: FP, z: FP) -> WilsonResult {
+    wilson_auto_with(successes, trials, z, |_, _| ())
+}
+
+/// Like [`wilson_auto`], but calls `on_swap(successes, trials)` with the original arguments when swapping them.
+///
+/// ```
+/// let mut swapped = false;
+/// let out = wilson::wilson_auto_with(20.0, 2.0, 2.0, |_, _| swapped = true);
+/// assert!(swapped);
+/// assert_eq!(out, wilson::wilson(2.0, 20.0, 2.0));
+/// ```
+#[must_use]
+pub fn wilson_auto_with(successes: FP, trials: FP, z: FP, on_swap: impl FnOnce(FP, FP)) -> WilsonResult {
+    if successes > trials && trials >= 0.0 {
+        on_swap(successes, trials);
+        wilson(trials, successes, z)
+    } else {
+        wilson(successes, trials, z)
+    }
+}
+
+/// Same as [`wilson`], but from natural logarithms of the counts, for counts too large for `FP`.
+///
+/// Only `successes / trials` and `z² / trials` are formed, both as `exp` of differences of the inputs,
+/// so e.g. `ln(trials) = 1000` works although `trials` itself would overflow. The bounds use the same
+/// cancellation-free form as [`wilson`] and then agree with it to a few units in the last place.
+/// The logarithms themselves limit the precision: an absolute rounding error of `ln(x)` is a relative one of `x`,
+/// so inputs around `1000` carry relative errors of about `1000 · EPSILON` into the bounds.
+/// `log_successes = -∞` stands for zero successes, and trials of `0.001` or less (including `-∞`) result in `[0, 1]`.
+///
+/// ```
+/// # use wilson::FP;
+/// let out = wilson::wilson_from_log_counts(FP::ln(10.0), FP::ln(20.0), 1.96);
+/// let reference = wilson::wilson(10.0, 20.0, 1.96);
+/// assert!((out.low - reference.low).abs() < 1e-6 && (out.high - reference.high).abs() < 1e-6);
+/// ```
+#[must_use]
+pub fn wilson_from_log_counts(log_successes: FP, log_trials: FP, z: FP) -> WilsonResult {
+    let z2 = z * z;
+    if log_trials <= (0.001 as FP).ln() || z2.is_infinite() {
+        return WilsonResult { low: 0.0, high: 1.0 };
+    }
+    let log_rate = log_successes - log_trials;
+    let rate = log_rate.exp();
+    if z == 0.0 {
+        return WilsonResult { low: rate, high: rate };
+    }
+    // `1 − rate` without cancellation for rates close to 1
+    let failure_rate = -log_rate.exp_m1();
+    // Everything from `wilson` divided through by `trials`, which only remains as `z² / trials`
+    let z2_per_trial = z2 * (-log_trials).exp();
+    let denominator = 1.0 + z2_per_trial;
+    let center = (rate + 0.5 * z2_per_trial) / denominator;
+    let complement = (failure_rate + 0.5 * z2_per_trial) / denominator;
+    let spread = (rate * failure_rate + 0.25 * z2_per_trial).sqrt();
+    let half_width = z * (-0.5 * log_trials).exp() / denominator * spread;
+    let low = rate * rate / denominator / (center + half_width);
+    let direct = center + half_width;
+    let high = if direct < 0.5 {
+        direct
+    } else {
+        1.0 - failure_rate * failure_rate / denominator / (complement + half_width)
+    };
+    let result = WilsonResult {
+        low: low.clamp(0.0, 1.0),
+        high: high.clamp(0.0, 1.0),
+    };
+    #[cfg(feature = "f32")]
+    let result = contain_estimate(result, rate, 1.0);
+    result
+}
+
+/// Continuity-corrected Wilson interval (Newcombe's method 4), more conservative than [`wilson`].
+///
+/// Each bound is the score bound for the successes shifted by half a trial away from the estimate,
+/// `s − ½` for the lower and `s + ½` for the upper one, which accounts for the binomial being discrete.
+/// Coverage then stays closer to (and mostly above) the nominal level, at the cost of a wider interval.
+///
+/// ```
+/// // Newcombe (1998), 81 out of 263
+/// let out = wilson::wilson_cc(81.0, 263.0, 1.959964);
+/// assert!((out.low - 0.2535).abs() < 1e-4 && (out.high - 0.3682).abs() < 1e-4);
+/// ```
+#[must_use]
+pub fn wilson_cc(successes: FP, trials: FP, z: FP) -> WilsonResult {
+    wilson_cc_custom(successes, trials, z, 1.0)
+}
+
+/// Same as [`wilson_cc`] with the continuity correction scaled by `correction`:
+/// the successes get shifted by `correction / 2` for each bound.
+///
+/// `1` gives the standard correction and `0` plain [`wilson`], values in between dial how conservative
+/// the interval is. Shifted successes are kept within `[0, trials]`, so there is no lower bound
+/// above 0 without successes and no upper bound below 1 without failures.
+#[must_use]
+pub fn wilson_cc_custom(successes: FP, trials: FP, z: FP, correction: FP) -> WilsonResult {
+    let shift = 0.5 * correction;
+    if shift == 0.0 {
+        return wilson(successes, trials, z);
+    }
+    let shifted = |s: FP| s.max(0.0).min(trials.max(0.0));
+    WilsonResult {
+        low: wilson(shifted(successes - shift), trials, z).low,
+        high: wilson(shifted(successes + shift), trials, z).high,
+    }
+}
+
+/// Wilson interval for a point-in-time survival probability `S(t) = P(T > t)`.
+///
+/// `survivors` out of `at_risk` subjects are treated as a plain binomial proportion.
+/// This matches the Kaplan-Meier estimate only when nobody got censored before `t`,
+/// so it is suitable for early `t` with many events and no late censoring.
+/// With substantial censoring use the Kaplan-Meier estimator with Greenwood's variance formula instead.
+///
+/// See [`is_survival_interval_valid`] for checking the inputs beforehand.
+#[must_use]
+pub fn wilson_survival(survivors: FP, at_risk: FP, z: FP) -> WilsonResult {
+    wilson(survivors, at_risk, z)
+}
+
+/// Check that `survivors` and `at_risk` are suitable for [`wilson_survival`]:
+/// both finite, `at_risk` positive and `survivors` between zero and `at_risk`.
+#[must_use]
+pub fn is_survival_interval_valid(survivors: FP, at_risk: FP) -> bool {
+    survivors.is_finite() && at_risk.is_finite() && at_risk > 0.0 && survivors >= 0.0 && survivors <= at_risk
+}
+
+/// Wilson interval for the observed agreement proportion between two raters on a binary outcome.
+///
+/// `agreements` is the number of rated items where both raters agree (both positive or both negative),
+/// `total` is the number of rated items.
+///
+/// This is an interval for the raw agreement `p_o`, not for Cohen's kappa itself.
+/// Kappa is `(p_o - p_e) / (1 - p_e)` where `p_e` is the agreement expected by chance from the raters' marginals,
+/// so for a fixed `p_e` the bounds can be mapped to approximate kappa bounds through the same linear transformation.
+#[must_use]
+pub fn wilson_icc(agreements: FP, total: FP, z: FP) -> WilsonResult {
+    wilson(agreements, total, z)
+}
+
+/// Interval for the pass rate of a flaky test that passed `passes` out of `runs` runs (retries included),
+/// at the two-sided `confidence` level. Check it against a required pass rate with [`WilsonResult::is_reliable`].
+///
+/// Each run counts as an independent trial. Zero `runs` result in the `[0, 1]` interval, which is never reliable.
+///
+/// ```
+/// // A test passed 197 of its last 200 runs in CI: can we trust it to pass 95% of the time?
+/// let interval = wilson::flaky_test_interval(197, 200, 0.95);
+/// assert!(interval.is_reliable(0.95));
+/// // Passing 19 out of 20 is the same rate, but too few runs to tell
+/// assert!(!wilson::flaky_test_interval(19, 20, 0.95).is_reliable(0.95));
+/// ```
+#[must_use]
+pub fn flaky_test_interval(passes: u32, runs: u32, confidence: FP) -> WilsonResult {
+    wilson_confidence(passes as FP, runs as FP, confidence)
+}
+
+/// Result of [`wilson_ratio_scale`]: Wilson interval bounds expressed as a rate per `scale` trials.
+#[derive(Debug, PartialEq, PartialOrd)]
+pub struct WilsonRateResult {
+    /// Lower bound of the rate
+    pub low_rate: FP,
+    /// Higher bound of the rate
+    pub high_rate: FP,
+    /// Number of trials the rate refers to, e.g. `1000` for "per 1000"
+    pub scale: FP,
+}
+
+/// Formats like `3.2–8.7 per 1000`. Precision (e.g. `{:.1}`) applies to both bounds.
+impl core::fmt::Display for WilsonRateResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(prec) = f.precision() {
+            write!(f, "{:.*}–{:.*} per {}", prec, self.low_rate, prec, self.high_rate, self.scale)
+        } else {
+            write!(f, "{}–{} per {}", self.low_rate, self.high_rate, self.scale)
+        }
+    }
+}
+
+/// Calculate the Wilson interval and express it as number of successes per `scale` trials.
+///
+/// ```
+/// let rate = wilson::wilson_ratio_scale(5.0, 1000.0, 2.0, 1000.0);
+/// assert_eq!(format!("{:.1}", rate), "2.1–11.8 per 1000");
+/// ```
+#[must_use]
+pub fn wilson_ratio_scale(successes: FP, trials: FP, z: FP, scale: FP) -> WilsonRateResult {
+    let r = wilson(successes, trials, z);
+    WilsonRateResult {
+        low_rate: r.low * scale,
+        high_rate: r.high * scale,
+        scale,
+    }
+}
+
+/// Interval for `ln(p)` using the delta method: the Wilson center `c` and half-width `d`
+/// become `ln(c) ± d / c`.
+///
+/// Returns a tuple instead of [`WilsonResult`] because the bounds are not probabilities.
+/// Exponentiating the bounds approximately gives the [`wilson`] bounds back when `trials` is large.
+/// The adjusted Wilson center is used instead of `successes / trials`, so zero `successes` still produce finite bounds.
+/// The upper bound is capped at `0`, which the symmetric margin can otherwise exceed when (nearly) all trials succeed.
+/// Zero `trials` result in `(-∞, 0)`.
+#[must_use]
+pub fn wilson_log_scale(successes: FP, trials: FP, z: FP) -> (FP, FP) {
+    if trials <= 0.001 {
+        return (FP::NEG_INFINITY, 0.0);
+    }
+    let parts = wilson_parts(successes, trials, z);
+    let center = parts.center.ln();
+    let margin = parts.half_width / parts.center;
+    (center - margin, (center + margin).min(0.0))
+}
+
+/// Interval for the log-odds `ln(p / (1 - p))` using the delta method:
+/// the Wilson center `c` and half-width `d` become `logit(c) ± d / (c·(1 - c))`.
+///
+/// Useful for comparing with logistic regression coefficients.
+/// Zero `trials` result in `(-∞, ∞)`.
+#[must_use]
+pub fn wilson_log_odds_scale(successes: FP, trials: FP, z: FP) -> (FP, FP) {
+    if trials <= 0.001 {
+        return (FP::NEG_INFINITY, FP::INFINITY);
+    }
+    let WilsonParts { center: p, half_width: d } = wilson_parts(successes, trials, z);
+    let center = (p / (1.0 - p)).ln();
+    let margin = d / (p * (1.0 - p));
+    (center - margin, center + margin)
+}
+
+/// Tolerance for comparing test results with reference values: the given one,
+/// or more if [`FP`] is too coarse for it.
+#[cfg(test)]
+pub(crate) const fn tol(tolerance: FP) -> FP {
+    tolerance.max(64.0 * FP::EPSILON)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        {
+            let out = wilson(1.0, 2.0, 2.0);
+            assert_abs_diff_eq!(out.low, 0.09175170954, epsilon = tol(1e-6));
+            assert_abs_diff_eq!(out.high, 0.9082482905, epsilon = tol(1e-6));
+        }
+        {
+            let out = wilson(10.0, 20.0, 2.0);
+            assert_abs_diff_eq!(out.low, 0.2958758548, epsilon = tol(1e-6));
+            assert_abs_diff_eq!(out.high, 0.7041241452, epsilon = tol(1e-6));
+        }
+        {
+            let out = wilson(2.0, 20.0, 2.0);
+            assert_abs_diff_eq!(out.low, 0.02722332891, epsilon = tol(1e-6));
+            assert_abs_diff_eq!(out.high, 0.3061100044, epsilon = tol(1e-6));
+        }
+        {
+            let out = wilson(2.0, 20.0, 3.0);
+            assert_abs_diff_eq!(out.low, 0.01595229175, epsilon = tol(1e-6));
+            assert_abs_diff_eq!(out.high, 0.4323235703, epsilon = tol(1e-6));
+        }
+        {
+            let out = wilson(20.0, 20.0, 2.0);
+            assert_abs_diff_eq!(out.low, 0.8333333333, epsilon = tol(1e-6));
+            assert_abs_diff_eq!(out.high, 1.0, epsilon = tol(1e-6));
+        }  {
+            let out = wilson(0.0, 20.0, 2.0);
+            assert_abs_diff_eq!(out.low, 0.0, epsilon = tol(1e-6));
+            assert_abs_diff_eq!(out.high, 0.1666666667, epsilon = tol(1e-6));
+        }
+    }
+
+    #[test]
+    fn degenerate() {
+        let out = wilson(0.0, 0.0, 2.0);
+        assert_abs_diff_eq!(out.low, 0.0, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(out.high, 1.0, epsilon = tol(1e-6));
+    }
+
+    #[test]
+    fn degenerate2() {
+        let out = wilson(0.005, 0.01, 2.0);
+        assert_abs_diff_eq!(out.low, 0.0006238305611, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(out.high, 0.9993761694, epsilon = tol(1e-6));
+    }
+
+    #[test]
+    fn ranges() {
+        let out = wilson(10.0, 20.0, 2.0);
+        assert_eq!(out.as_range(), out.low..=out.high);
+        assert!(out.contains(&0.5));
+        assert!(out.contains(&out.low));
+        assert!(out.contains(&out.high));
+        assert!(!out.contains(&0.1));
+        assert!((0.0..=1.0).contains(&out.low));
+        assert!((0.0..=1.0).contains(&out.high));
+
+        let sorted = [0.05, 0.1, 0.2, 0.25, 0.6, 0.8, 0.95];
+        let found = sorted.binary_search_by(|x| {
+            if out.contains(x) {
+                core::cmp::Ordering::Equal
+            } else if *x < out.low {
+                core::cmp::Ordering::Less
+            } else {
+                core::cmp::Ordering::Greater
+            }
+        });
+        assert_eq!(found, Ok(4));
+
+        let range: RangeInclusive<FP> = wilson(2.0, 20.0, 2.0).into();
+        let back = WilsonResult::from(range);
+        assert_eq!(back, wilson(2.0, 20.0, 2.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn inverted_range() {
+        let _ = WilsonResult::from(0.7..=0.3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_unit_range() {
+        let _ = WilsonResult::from(0.5..=1.5);
+    }
+
+    #[test]
+    fn survival() {
+        let out = wilson_survival(100.0, 100.0, 2.0);
+        assert_abs_diff_eq!(out.low, 0.9615384615, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(out.high, 1.0, epsilon = tol(1e-6));
+        assert_eq!(wilson_survival(7.0, 20.0, 2.0), wilson(7.0, 20.0, 2.0));
+
+        assert!(is_survival_interval_valid(7.0, 20.0));
+        assert!(is_survival_interval_valid(20.0, 20.0));
+        assert!(!is_survival_interval_valid(21.0, 20.0));
+        assert!(!is_survival_interval_valid(-1.0, 20.0));
+        assert!(!is_survival_interval_valid(0.0, 0.0));
+        assert!(!is_survival_interval_valid(FP::NAN, 20.0));
+    }
+
+    #[test]
+    fn checked() {
+        assert_eq!(try_wilson(1.0, 2.0, 2.0), Ok(wilson(1.0, 2.0, 2.0)));
+        assert_eq!(try_wilson(0.0, 0.0, 2.0), Ok(wilson(0.0, 0.0, 2.0)));
+        assert_eq!(try_wilson(FP::NAN, 2.0, 2.0), Err(WilsonError::NonFinite));
+        assert_eq!(try_wilson(1.0, 2.0, FP::INFINITY), Err(WilsonError::NonFinite));
+        assert_eq!(try_wilson(-1.0, 2.0, 2.0), Err(WilsonError::Negative));
+        assert_eq!(try_wilson(3.0, 2.0, 2.0), Err(WilsonError::SuccessesExceedTrials));
+    }
+
+    #[test]
+    fn checked_batch() {
+        let data = [
+            (1.0, 2.0),
+            (3.0, 2.0),
+            (10.0, 20.0),
+            (FP::NAN, 20.0),
+            (0.0, 0.0),
+            (2.0, FP::INFINITY),
+        ];
+        let out = try_wilson_batch(&data, 2.0);
+        assert_eq!(out.len(), data.len());
+        assert_eq!(out[0], Ok(wilson(1.0, 2.0, 2.0)));
+        assert_eq!(out[1], Err(WilsonError::SuccessesExceedTrials));
+        assert_eq!(out[2], Ok(wilson(10.0, 20.0, 2.0)));
+        assert_eq!(out[3], Err(WilsonError::NonFinite));
+        assert_eq!(out[4], Ok(wilson(0.0, 0.0, 2.0)));
+        assert_eq!(out[5], Err(WilsonError::NonFinite));
+        assert!(try_wilson_batch(&[], 2.0).is_empty());
+    }
+
+    #[test]
+    fn agreement() {
+        let out = wilson_icc(50.0, 50.0, 2.0);
+        assert!(out.low > 0.9);
+        assert_abs_diff_eq!(out.high, 1.0, epsilon = tol(1e-6));
+
+        let out = wilson_icc(10.0, 20.0, 2.0);
+        assert_abs_diff_eq!(out.low + out.high, 1.0, epsilon = tol(1e-6));
+        assert!(out.high - out.low > 0.4);
+    }
+
+    #[test]
+    fn precision_score() {
+        let out = wilson(0.0, 0.0, 2.0);
+        assert_abs_diff_eq!(out.width(), 1.0, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(out.precision(), 0.0, epsilon = tol(1e-6));
+
+        let out = wilson(500000.0, 1000000.0, 2.0);
+        assert!(out.precision() > 0.99);
+        assert!(out.precision() <= 1.0);
+        assert!(wilson(5.0, 10.0, 2.0).precision() < wilson(50.0, 100.0, 2.0).precision());
+    }
+
+    #[test]
+    fn rate_scale() {
+        let out = wilson(2.0, 20.0, 2.0);
+        let rate = wilson_ratio_scale(2.0, 20.0, 2.0, 1.0);
+        assert_eq!(rate.low_rate, out.low);
+        assert_eq!(rate.high_rate, out.high);
+        let rate = wilson_ratio_scale(2.0, 20.0, 2.0, 100.0);
+        assert_abs_diff_eq!(rate.low_rate, out.low * 100.0, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(rate.high_rate, out.high * 100.0, epsilon = tol(1e-6));
+        assert_eq!(rate.scale, 100.0);
+        assert_eq!(format!("{:.2}", rate), "2.72–30.61 per 100");
+    }
+
+    #[test]
+    fn lower_bound_ordering() {
+        use core::cmp::Ordering;
+
+        let a = WilsonResult { low: 0.3, high: 0.5 };
+        let b = WilsonResult { low: 0.3 + 1e-7, high: 0.4 };
+        assert_eq!(a.lower_bound_cmp(&b, 1e-6), Ordering::Equal);
+        assert_eq!(b.lower_bound_cmp(&a, 1e-6), Ordering::Equal);
+        assert_eq!(a.lower_bound_cmp(&b, 0.0), Ordering::Less);
+        assert_eq!(b.lower_bound_cmp(&a, 0.0), Ordering::Greater);
+
+        let c = WilsonResult { low: 0.31, high: 0.32 };
+        assert_eq!(a.lower_bound_cmp(&c, 1e-6), Ordering::Less);
+        assert_eq!(c.lower_bound_cmp(&b, 1e-6), Ordering::Greater);
+        assert_eq!(c.lower_bound_cmp(&c, 0.0), Ordering::Equal);
+
+        // A stable sort keeps the original order of near-ties
+        let mut items = [("first", b), ("second", c), ("third", a)];
+        items.sort_by(|x, y| y.1.lower_bound_cmp(&x.1, 1e-6));
+        let order: Vec<&str> = items.iter().map(|x| x.0).collect();
+        assert_eq!(order, ["second", "first", "third"]);
+    }
+
+    #[test]
+    fn containment() {
+        let outer = WilsonResult { low: 0.25, high: 0.75 };
+        let strict = WilsonResult { low: 0.375, high: 0.5 };
+        assert!(outer.contains_interval(&strict));
+        assert!(strict.is_contained_by(&outer));
+        assert!(!strict.contains_interval(&outer));
+        assert_eq!(strict.containment_margin(&outer), Some(0.125));
+
+        let touching = WilsonResult { low: 0.25, high: 0.5 };
+        assert!(outer.contains_interval(&touching));
+        assert_eq!(touching.containment_margin(&outer), Some(0.0));
+        assert_eq!(outer.containment_margin(&outer), Some(0.0));
+
+        let partial = WilsonResult { low: 0.5, high: 0.875 };
+        assert!(!outer.contains_interval(&partial));
+        assert!(!partial.contains_interval(&outer));
+        assert!(!partial.is_contained_by(&outer));
+        assert_eq!(partial.containment_margin(&outer), None);
+
+        // More data gives a nested interval here
+        assert!(wilson(10.0, 40.0, 2.0).contains_interval(&wilson(100.0, 400.0, 2.0)));
+    }
+
+    #[test]
+    fn area_around_threshold() {
+        let r = WilsonResult { low: 0.25, high: 0.75 };
+        assert_eq!(r.wilson_area_above_threshold(0.8), 0.0);
+        assert_eq!(r.wilson_area_below_threshold(0.8), 1.0);
+        assert_eq!(r.wilson_area_above_threshold(0.1), 1.0);
+        assert_eq!(r.wilson_area_below_threshold(0.1), 0.0);
+        assert_eq!(r.wilson_area_above_threshold(0.375), 0.75);
+        assert_eq!(r.wilson_area_below_threshold(0.375), 0.25);
+
+        // Ends of the interval
+        assert_eq!(r.wilson_area_above_threshold(0.75), 0.0);
+        assert_eq!(r.wilson_area_below_threshold(0.75), 1.0);
+        assert_eq!(r.wilson_area_above_threshold(0.25), 1.0);
+        assert_eq!(r.wilson_area_below_threshold(0.25), 0.0);
+
+        let out = wilson(3.0, 10.0, 1.96);
+        for i in 0..=100 {
+            let t = out.low + out.width() * i as FP / 100.0;
+            let sum = out.wilson_area_above_threshold(t) + out.wilson_area_below_threshold(t);
+            assert_abs_diff_eq!(sum, 1.0, epsilon = tol(1e-12));
+        }
+
+        // Zero width does not divide by zero
+        let point = WilsonResult { low: 0.5, high: 0.5 };
+        assert_eq!(point.wilson_area_above_threshold(0.5), 0.0);
+        assert_eq!(point.wilson_area_below_threshold(0.5), 1.0);
+    }
+
+    #[test]
+    fn log_counts() {
+        for (s, n, z) in [(10.0, 20.0, 1.96), (0.0, 50.0, 1.0), (7.0, 7.0, 2.576), (1.0, 1e9, 1.96), (0.5, 3.0, 5.0)] {
+            let out = wilson_from_log_counts(FP::ln(s), FP::ln(n), z);
+            let reference = wilson(s, n, z);
+            assert_abs_diff_eq!(out.low, reference.low, epsilon = tol(1e-12) + 1e-9 * reference.low);
+            assert_abs_diff_eq!(out.high, reference.high, epsilon = tol(1e-12) + 1e-9 * reference.high);
+        }
+        assert_eq!(wilson_from_log_counts(0.0, FP::NEG_INFINITY, 1.96), WilsonResult { low: 0.0, high: 1.0 });
+        let exact = wilson_from_log_counts(FP::ln(3.0), FP::ln(4.0), 0.0);
+        assert_abs_diff_eq!(exact.low, 0.75, epsilon = tol(0.0));
+        assert_eq!(exact.low, exact.high);
+
+        // Far beyond `FP::MAX`, the interval collapses around the rate
+        let (log_n, log_rate) = (1000.0, -1.0);
+        let out = wilson_from_log_counts(log_n + log_rate, log_n, 1.96);
+        let rate = FP::exp(log_rate);
+        assert!(out.low <= rate && rate <= out.high);
+        assert!(out.width() < tol(1e-12));
+        // Shrinking like `1 / sqrt(trials)` at counts where single precision has no width left
+        if cfg!(feature = "f64") {
+            let wide = wilson_from_log_counts(29.0, 30.0, 1.96);
+            let narrow = wilson_from_log_counts(31.0, 32.0, 1.96);
+            assert_abs_diff_eq!(wide.width() / narrow.width(), FP::exp(1.0), epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn continuity_correction() {
+        // Newcombe (1998), table I, method 4
+        let z = 1.959963984540054;
+        for (s, n, low, high) in [
+            (81.0, 263.0, 0.2535, 0.3682),
+            (15.0, 148.0, 0.0598, 0.1644),
+            (0.0, 20.0, 0.0, 0.2005),
+            (1.0, 29.0, 0.0018, 0.1963),
+        ] {
+            let out = wilson_cc(s, n, z);
+            assert_abs_diff_eq!(out.low, low, epsilon = 5e-5);
+            assert_abs_diff_eq!(out.high, high, epsilon = 5e-5);
+        }
+        assert_eq!(wilson_cc(20.0, 20.0, z).high, 1.0);
+
+        for (s, n) in [(0.0, 10.0), (3.0, 10.0), (81.0, 263.0), (7.5, 12.0), (12.0, 12.0), (0.0, 0.0)] {
+            assert_eq!(wilson_cc_custom(s, n, z, 0.0), wilson(s, n, z));
+            assert_eq!(wilson_cc_custom(s, n, z, 1.0), wilson_cc(s, n, z));
+            // Halfway correction lies between none and the full one
+            let (plain, half, full) = (wilson(s, n, z), wilson_cc_custom(s, n, z, 0.5), wilson_cc(s, n, z));
+            assert!(full.low <= half.low && half.low <= plain.low);
+            assert!(plain.high <= half.high && half.high <= full.high);
+        }
+        let half = wilson_cc_custom(81.0, 263.0, z, 0.5);
+        assert_abs_diff_eq!(half.low, wilson(80.75, 263.0, z).low, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(half.high, wilson(81.25, 263.0, z).high, epsilon = tol(1e-12));
+    }
+
+    #[test]
+    fn lazy() {
+        let lazy = LazyWilson::new(2.0, 7.0, 1.5);
+        assert!(!lazy.is_computed());
+        let first = lazy.get();
+        assert!(lazy.is_computed());
+        assert_eq!(first, wilson(2.0, 7.0, 1.5));
+        assert_eq!(lazy.get(), first);
+        assert_eq!(lazy.clone().get(), first);
+
+        let nan = LazyWilson::new(FP::NAN, 7.0, 1.5);
+        assert_eq!(nan.get().low.to_bits(), nan.get().low.to_bits());
+        assert_eq!(LazyWilson::default().get(), wilson(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn odds() {
+        let even = WilsonResult { low: 0.5, high: 0.5 };
+        assert_eq!(even.to_odds(), (1.0, 1.0));
+        assert_eq!(even.to_log_odds(), (0.0, 0.0));
+
+        let edges = WilsonResult { low: 0.0, high: 1.0 };
+        assert_eq!(edges.to_odds(), (0.0, FP::INFINITY));
+        assert_eq!(edges.to_log_odds(), (FP::NEG_INFINITY, FP::INFINITY));
+        assert_eq!(WilsonResult::from_odds(0.0, FP::INFINITY), edges);
+
+        for out in [wilson(3.0, 10.0, 1.96), wilson(1.0, 200.0, 2.576), wilson(9.0, 10.0, 1.0)] {
+            let (low, high) = out.to_odds();
+            assert!(low < high);
+            let back = WilsonResult::from_odds(low, high);
+            assert_abs_diff_eq!(back.low, out.low, epsilon = tol(1e-12));
+            assert_abs_diff_eq!(back.high, out.high, epsilon = tol(1e-12));
+            let (log_low, log_high) = out.to_log_odds();
+            assert_abs_diff_eq!(log_low.exp(), low, epsilon = tol(1e-12));
+            assert_abs_diff_eq!(log_high.exp(), high, epsilon = tol(1e-12));
+        }
+        let (low, high) = WilsonResult { low: 0.2, high: 0.75 }.to_odds();
+        assert_abs_diff_eq!(low, 0.25, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(high, 3.0, epsilon = tol(1e-12));
+    }
+
+    #[test]
+    fn rounding_outward() {
+        let r = WilsonResult { low: 0.296, high: 0.704 };
+        assert_eq!(r.rounded(1), WilsonResult { low: 0.2, high: 0.8 });
+        assert_eq!(r.rounded(2), WilsonResult { low: 0.29, high: 0.71 });
+        assert_eq!(r.rounded(3), WilsonResult { low: 0.296, high: 0.704 });
+        assert_eq!(r.rounded(0), WilsonResult { low: 0.0, high: 1.0 });
+
+        // Bounds on the grid stay, even with representation noise
+        let exact = WilsonResult { low: 0.1 + 0.2, high: 0.7 };
+        assert_eq!(exact.rounded(1), WilsonResult { low: 0.3, high: 0.7 });
+
+        // Never collapses or understates
+        let narrow = WilsonResult { low: 0.5001, high: 0.5002 };
+        let shown = narrow.rounded(2);
+        assert_eq!(shown, WilsonResult { low: 0.5, high: 0.51 });
+        for out in [wilson(3.0, 10.0, 1.96), wilson(0.0, 7.0, 2.0), wilson(999.0, 1000.0, 1.0)] {
+            for decimals in 0..6 {
+                let shown = out.rounded(decimals);
+                assert!(shown.contains_interval(&out));
+            }
+        }
+    }
+
+    #[test]
+    fn posterior_summary() {
+        for (s, n, z) in [(3.0, 10.0, 1.96), (0.0, 20.0, 1.0), (7.0, 7.0, 2.576), (45.5, 100.0, 1.5)] {
+            let out = wilson(s, n, z);
+            assert_abs_diff_eq!(wilson_posterior_mean(s, n, z), out.midpoint(), epsilon = tol(1e-12));
+        }
+        assert_eq!(wilson_posterior_mean(0.0, 0.0, 1.96), 0.5);
+
+        // Beta(2, 3): 6 / (25 · 6)
+        assert_abs_diff_eq!(wilson_posterior_variance(1.0, 3.0, 2.0f64.sqrt() as FP), 0.04, epsilon = tol(1e-12));
+        let mut previous = FP::INFINITY;
+        for n in [1.0, 5.0, 20.0, 100.0, 1000.0, 1e6] {
+            let variance = wilson_posterior_variance(0.3 * n, n, 1.96);
+            assert!(variance < previous);
+            previous = variance;
+        }
+        assert!(wilson_posterior_variance(0.0, 0.0, 0.0).is_nan());
+    }
+
+    #[test]
+    fn flaky_tests() {
+        let out = flaky_test_interval(197, 200, 0.95);
+        assert_eq!(out, wilson_confidence(197.0, 200.0, 0.95));
+        assert!(out.is_reliable(0.95));
+        assert!(!out.is_reliable(out.low));
+        assert!(!flaky_test_interval(19, 20, 0.95).is_reliable(0.9));
+        assert!(flaky_test_interval(20, 20, 0.95).is_reliable(0.8));
+
+        let never_run = flaky_test_interval(0, 0, 0.95);
+        assert_eq!(never_run, WilsonResult { low: 0.0, high: 1.0 });
+        assert!(!never_run.is_reliable(0.0));
+    }
+
+    #[test]
+    fn score_test() {
+        // (0.3 − 0.2) / sqrt(0.2 · 0.8 / 50) = 0.1 / 0.0565685...
+        assert_abs_diff_eq!(score_statistic(15.0, 50.0, 0.2), 1.7677669529663689, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(score_statistic(5.0, 50.0, 0.2), -1.7677669529663689, epsilon = tol(1e-12));
+        assert!(score_statistic(15.0, 50.0, 0.29) > 0.0);
+        assert_eq!(score_statistic(15.0, 50.0, 0.3), 0.0);
+        assert!(score_statistic(15.0, 50.0, 0.31) < 0.0);
+
+        assert_eq!(score_statistic(3.0, 10.0, 0.0), FP::INFINITY);
+        assert_eq!(score_statistic(3.0, 10.0, 1.0), FP::NEG_INFINITY);
+        assert_eq!(score_statistic(0.0, 10.0, 0.0), 0.0);
+        assert_eq!(score_statistic(10.0, 10.0, 1.0), 0.0);
+        assert!(score_statistic(0.0, 0.0, 0.5).is_nan());
+
+        // The interval ends are where the statistic reaches ±z
+        let out = wilson(15.0, 50.0, 1.5);
+        assert_abs_diff_eq!(score_statistic(15.0, 50.0, out.low), 1.5, epsilon = tol(1e-9));
+        assert_abs_diff_eq!(score_statistic(15.0, 50.0, out.high), -1.5, epsilon = tol(1e-9));
+    }
+
+    #[test]
+    fn surprise() {
+        let prior = WilsonResult { low: 0.25, high: 0.75 };
+        assert_eq!(wilson_surprise(&prior, 5.0, 10.0), 0.0);
+        assert_eq!(wilson_z_score(&prior, 0.5), 0.0);
+        assert_eq!(wilson_surprise(&prior, 3.0, 8.0), 0.5);
+        assert_eq!(wilson_surprise(&prior, 5.0, 8.0), 0.5);
+        assert_eq!(wilson_surprise(&prior, 1.0, 10.0), 1.0);
+        assert_eq!(wilson_surprise(&prior, 9.0, 10.0), 1.0);
+        assert_eq!(wilson_surprise(&prior, 0.0, 0.0), 0.0);
+
+        // At the ends of the prior the z-score is ±1.96
+        assert_abs_diff_eq!(wilson_z_score(&prior, 0.75), 1.96, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(wilson_z_score(&prior, 0.25), -1.96, epsilon = tol(1e-12));
+
+        let prior = wilson(30.0, 100.0, 1.96);
+        assert_abs_diff_eq!(wilson_surprise(&prior, prior.midpoint() * 50.0, 50.0), 0.0, epsilon = tol(1e-12));
+        assert!(wilson_surprise(&prior, 4.0, 10.0) > wilson_surprise(&prior, 3.0, 10.0));
+        assert!(wilson_z_score(&prior, 0.4) > 0.0 && wilson_z_score(&prior, 0.2) < 0.0);
+    }
+
+    #[test]
+    fn write_to_buffer() {
+        let out = WilsonResult { low: 0.25, high: 0.5 };
+        assert_eq!(format!("{}", out), "0.25–0.5");
+        assert_eq!(format!("{:.3}", out), "0.250–0.500");
+
+        let mut buf = [0u8; 16];
+        let len = out.write_to(&mut buf).unwrap();
+        assert_eq!(&buf[..len], "0.25–0.5".as_bytes());
+        let len = wilson(2.0, 20.0, 2.0).write_to(&mut [0u8; 64]).unwrap();
+        assert_eq!(len, wilson(2.0, 20.0, 2.0).to_string().len());
+
+        let mut small = [0u8; 5];
+        assert_eq!(out.write_to(&mut small), Err(core::fmt::Error));
+        assert_eq!(&small, b"0.25\xe2");
+        assert_eq!(out.write_to(&mut []), Err(core::fmt::Error));
+    }
+
+    #[test]
+    fn confidence_level() {
+        assert_abs_diff_eq!(z_from_confidence(0.95), 1.959964, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(z_from_confidence(0.6827), 1.0, epsilon = 0.0001);
+        assert!(z_from_confidence(1.5).is_nan());
+
+        let out = wilson_confidence(2.0, 20.0, 0.95);
+        assert_eq!(out, wilson(2.0, 20.0, z_from_confidence(0.95)));
+        assert_eq!(out, wilson_with_quantile(2.0, 20.0, 0.95, z_from_confidence));
+        assert_eq!(wilson_with_quantile(2.0, 20.0, 0.95, |_| 3.0), wilson(2.0, 20.0, 3.0));
+    }
+
+    /// `hi + lo` with about twice the precision of [`FP`], as a reference for rounding errors.
+    #[derive(Clone, Copy)]
+    struct DoubleFP(FP, FP);
+
+    impl DoubleFP {
+        fn normalized(hi: FP, lo: FP) -> Self {
+            let sum = hi + lo;
+            DoubleFP(sum, lo - (sum - hi))
+        }
+
+        fn add(self, other: Self) -> Self {
+            let sum = self.0 + other.0;
+            let virtual_other = sum - self.0;
+            let error = (self.0 - (sum - virtual_other)) + (other.0 - virtual_other);
+            Self::normalized(sum, error + self.1 + other.1)
+        }
+
+        fn mul(self, other: Self) -> Self {
+            let product = self.0 * other.0;
+            let error = self.0.mul_add(other.0, -product);
+            Self::normalized(product, error + self.0 * other.1 + self.1 * other.0)
+        }
+
+        fn div(self, other: Self) -> Self {
+            let quotient = self.0 / other.0;
+            let remainder = self.add(other.mul(DoubleFP(-quotient, 0.0)));
+            Self::normalized(quotient, remainder.0 / other.0)
+        }
+
+        fn sqrt(self) -> Self {
+            let root = self.0.sqrt();
+            let remainder = self.add(DoubleFP(-root, 0.0).mul(DoubleFP(root, 0.0)));
+            Self::normalized(root, remainder.0 / (2.0 * root))
+        }
+
+        /// Distance of `x` from this value
+        fn error(self, x: FP) -> FP {
+            ((x - self.0) - self.1).abs()
+        }
+    }
+
+    #[test]
+    fn fused_matches_plain() {
+        let ulp = |x: FP| x.next_up() - x;
+        for n in [0.01, 1.0, 2.0, 7.0, 20.0, 1000.0, 1e9] {
+            for k in 0..=10 {
+                let s = n * k as FP / 10.0;
+                for z in [0.5, 1.0, 1.96, 3.0] {
+                    let [plain, fused] = [false, true].map(|fused| generic::wilson_bounds(s, n, z, fused));
+
+                    let [s_, n_, z_, half, quarter] = [s, n, z, 0.5, 0.25].map(|x| DoubleFP(x, 0.0));
+                    let z2 = z_.mul(z_);
+                    let denominator = n_.add(z2);
+                    let center = s_.add(half.mul(z2)).div(denominator);
+                    let failures = n_.add(s_.mul(DoubleFP(-1.0, 0.0)));
+                    let margin = z_.div(denominator).mul(s_.mul(failures).div(n_).add(quarter.mul(z2)).sqrt());
+                    let exact_low = center.add(margin.mul(DoubleFP(-1.0, 0.0)));
+                    let exact_high = center.add(margin);
+
+                    // Within a unit in the last place, unless the fused result is closer to the exact value
+                    let pairs = [(plain.0, fused.0, exact_low), (plain.1, fused.1, exact_high)];
+                    for (plain, fused, exact) in pairs {
+                        assert!(
+                            (plain - fused).abs() <= ulp(plain) || exact.error(fused) <= exact.error(plain),
+                            "{s} {n} {z}: plain {plain}, fused {fused}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn monotone_in_successes() {
+        for n in [0.002, 0.5, 1.0, 2.0, 3.7, 10.0, 100.0, 1e4, 1e6, 1e9] {
+            for z in [0.0, 0.1, 0.5, 1.0, 1.96, 5.0, 100.0] {
+                // An even grid with fractional steps, plus values approaching both ends geometrically
+                let steps = 2000;
+                let mut successes: Vec<FP> = (0..=steps).map(|i| n * i as FP / steps as FP).collect();
+                for k in 1..40 {
+                    let tiny = n * (0.5 as FP).powi(k);
+                    successes.extend([tiny, n - tiny]);
+                }
+                successes.sort_by(|a, b| a.total_cmp(b));
+                let mut previous = wilson(0.0, n, z);
+                for &s in &successes[1..] {
+                    let out = wilson(s, n, z);
+                    assert!(out.low >= previous.low, "low decreased at {s} of {n}, z = {z}");
+                    assert!(out.high >= previous.high, "high decreased at {s} of {n}, z = {z}");
+                    previous = out;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rare_events() {
+        // References computed with 50-digit mpmath
+        let cases = [
+            (1.0, 1e9, 1.96, 1.7652001430606605e-10, 5.665079963252843e-9),
+            (1.0, 1e15, 3.0, 9.167308680401607e-17, 1.0908326913195885e-14),
+            (0.0, 1e12, 1.96, 0.0, 3.841599999985242e-12),
+        ];
+        for (s, n, z, low, high) in cases {
+            let out = wilson(s, n, z);
+            assert_abs_diff_eq!(out.low, low, epsilon = 16.0 * FP::EPSILON * low);
+            assert_abs_diff_eq!(out.high, high, epsilon = 16.0 * FP::EPSILON * high);
+        }
+    }
+
+    #[test]
+    fn zero_z() {
+        for (s, n) in [(0.0, 20.0), (2.0, 20.0), (1.0, 3.0), (7.0, 7.0), (0.005, 0.01), (1.0, 1e9)] {
+            let out = wilson(s, n, 0.0);
+            assert_eq!(out.low, s / n);
+            assert_eq!(out.high, s / n);
+            assert_eq!(out.width(), 0.0);
+        }
+        assert_eq!(wilson(0.0, 0.0, 0.0), WilsonResult { low: 0.0, high: 1.0 });
+    }
+
+    #[test]
+    fn product() {
+        let a = WilsonResult { low: 0.8, high: 0.9 };
+        let b = WilsonResult { low: 0.5, high: 0.6 };
+        let out = a.and(&b);
+        assert_abs_diff_eq!(out.low, 0.4, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(out.high, 0.54, epsilon = tol(1e-6));
+
+        let unknown = wilson(0.0, 0.0, 2.0);
+        assert_eq!(a.and(&unknown), WilsonResult { low: 0.0, high: 0.9 });
+    }
+
+    #[test]
+    fn log_scales() {
+        let out = wilson(300.0, 1000.0, 2.0);
+        let (low, high) = wilson_log_scale(300.0, 1000.0, 2.0);
+        assert_abs_diff_eq!(low.exp(), out.low, epsilon = 0.002);
+        assert_abs_diff_eq!(high.exp(), out.high, epsilon = 0.002);
+
+        let (low, high) = wilson_log_odds_scale(300.0, 1000.0, 2.0);
+        let inv_logit = |x: FP| 1.0 / (1.0 + (-x).exp());
+        assert_abs_diff_eq!(inv_logit(low), out.low, epsilon = 0.002);
+        assert_abs_diff_eq!(inv_logit(high), out.high, epsilon = 0.002);
+
+        let (low, high) = wilson_log_odds_scale(500.0, 1000.0, 2.0);
+        assert_abs_diff_eq!(low, -high, epsilon = tol(1e-6));
+
+        let (low, high) = wilson_log_scale(0.0, 20.0, 2.0);
+        assert!(low.is_finite() && high.is_finite());
+
+        // All successes: the margin reaches past `ln 1`, but the bound stays a log probability
+        let (low, high) = wilson_log_scale(10.0, 10.0, 2.0);
+        assert_eq!(high, 0.0);
+        assert!(low.is_finite() && low < 0.0);
+        for n in [1.0, 3.0, 1000.0, 1e9] {
+            assert!(wilson_log_scale(n, n, 1.96).1 <= 0.0);
+        }
+    }
+
+    #[test]
+    fn labeled() {
+        let out = LabeledInterval::wilson(2.0, 20.0, 0.95);
+        assert_eq!(out.result, wilson_confidence(2.0, 20.0, 0.95));
+        assert_eq!(out.confidence, 0.95);
+        assert_eq!(out.method, IntervalMethod::Wilson);
+    }
+
+    #[test]
+    fn builder() {
+        let out = WilsonBuilder::new(2.0, 20.0).confidence(0.95).try_build().unwrap();
+        assert_eq!(out, LabeledInterval::wilson(2.0, 20.0, 0.95));
+        let out = WilsonBuilder::new(2.0, 20.0).z(2.0).try_build().unwrap();
+        assert_eq!(out.result, wilson(2.0, 20.0, 2.0));
+        assert_eq!(out.confidence, confidence_from_z(2.0));
+
+        let builder = WilsonBuilder::new(2.0, 20.0);
+        assert_eq!(builder.try_build(), Err(BuildError::MissingLevel));
+        assert_eq!(builder.z(2.0).confidence(0.9).try_build(), Err(BuildError::ConflictingLevels));
+        assert_eq!(builder.confidence(1.0).try_build(), Err(BuildError::InvalidConfidence));
+        assert_eq!(builder.confidence(FP::NAN).try_build(), Err(BuildError::InvalidConfidence));
+        assert_eq!(builder.z(-1.0).try_build(), Err(BuildError::Invalid(WilsonError::InvalidZ)));
+        assert_eq!(
+            WilsonBuilder::new(30.0, 20.0).confidence(0.95).try_build(),
+            Err(BuildError::Invalid(WilsonError::SuccessesExceedTrials))
+        );
+
+        let calibrated = builder.method(IntervalMethod::CalibratedWilson);
+        let out = calibrated.confidence(0.95).try_build().unwrap();
+        assert_eq!(out.method, IntervalMethod::CalibratedWilson);
+        assert_eq!(out.confidence, 0.95);
+        assert_eq!(out.result, wilson(2.0, 20.0, calibrated_z(20, 0.95)));
+        assert!(out.result.width() > wilson_confidence(2.0, 20.0, 0.95).width());
+        let out = calibrated.confidence(1.0 - 2.0 * FP::EPSILON).try_build().unwrap();
+        assert_eq!(out.result, WilsonResult { low: 0.0, high: 1.0 });
+        assert_eq!(calibrated.z(2.0).try_build(), Err(BuildError::NeedsConfidence));
+        assert_eq!(calibrated.z(2.0).confidence(0.9).try_build(), Err(BuildError::ConflictingLevels));
+        assert_eq!(
+            WilsonBuilder::new(2.0, 20.5).confidence(0.95).method(IntervalMethod::CalibratedWilson).try_build(),
+            Err(BuildError::FractionalTrials)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn labeled_serde_round_trip() {
+        let out = LabeledInterval::wilson(2.0, 20.0, 0.95);
+        let json = serde_json::to_string(&out).unwrap();
+        assert!(json.contains("\"method\":\"Wilson\""));
+        let back: LabeledInterval = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, out);
+    }
+
+    #[test]
+    fn auto_swap() {
+        let mut reported = None;
+        let out = wilson_auto_with(20.0, 2.0, 2.0, |s, n| reported = Some((s, n)));
+        assert_eq!(out, wilson(2.0, 20.0, 2.0));
+        assert_eq!(reported, Some((20.0, 2.0)));
+
+        let mut reported = None;
+        let out = wilson_auto_with(2.0, 20.0, 2.0, |s, n| reported = Some((s, n)));
+        assert_eq!(out, wilson(2.0, 20.0, 2.0));
+        assert_eq!(reported, None);
+
+        assert_eq!(wilson_auto(20.0, 20.0, 2.0), wilson(20.0, 20.0, 2.0));
+        assert_eq!(wilson_auto(20.0, 2.0, 2.0), wilson(2.0, 20.0, 2.0));
+    }
+
+    #[test]
+    fn standard_error() {
+        let out = wilson(300.0, 1000.0, 1.96);
+        let expected = (0.3 * 0.7 / 1000.0 as FP).sqrt();
+        assert_abs_diff_eq!(out.to_standard_error(1.96), expected, epsilon = 0.0002);
+        assert_abs_diff_eq!(out.to_variance(1.96), expected * expected, epsilon = tol(2e-6));
+    }
+
+    #[test]
+    fn matches_r() {
+        assert_abs_diff_eq!(z_from_confidence(0.95), Z_95_EXACT, epsilon = tol(1e-12));
+        // prop.test(10, 20, correct = FALSE)$conf.int
+        let out = wilson_confidence(10.0, 20.0, 0.95);
+        assert_abs_diff_eq!(out.low, 0.2992980, epsilon = tol(1e-7));
+        assert_abs_diff_eq!(out.high, 0.7007020, epsilon = tol(1e-7));
+        // prop.test(30, 100, correct = FALSE)$conf.int
+        let out = wilson_confidence(30.0, 100.0, 0.95);
+        assert_abs_diff_eq!(out.low, 0.2189489, epsilon = tol(1e-7));
+        assert_abs_diff_eq!(out.high, 0.3958485, epsilon = tol(1e-7));
+        // Rounded 1.96 is visibly off at this precision
+        assert!((wilson(30.0, 100.0, 1.96).low - 0.2189489).abs() > 1e-6);
+    }
+
+    #[test]
+    fn predictive() {
+        let narrow = wilson(300.0, 1000.0, 2.0);
+        let wide = wilson(3.0, 10.0, 2.0);
+        assert!(wide.posterior_predictive(50.0, 2.0).width() > narrow.posterior_predictive(50.0, 2.0).width());
+        assert!(narrow.posterior_predictive(50.0, 2.0).width() > narrow.width());
+
+        let far = narrow.posterior_predictive(1e12, 2.0);
+        assert_abs_diff_eq!(far.midpoint(), narrow.midpoint(), epsilon = tol(1e-6));
+        assert_abs_diff_eq!(far.low, narrow.low, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(far.high, narrow.high, epsilon = tol(1e-6));
+
+        let out = wilson(0.0, 5.0, 2.0).posterior_predictive(10.0, 2.0);
+        assert_eq!(out.low, 0.0);
+    }
+
+    #[test]
+    fn width_to_z() {
+        for (s, n, target) in [(10.0, 20.0, 0.3), (2.0, 200.0, 0.05), (0.0, 50.0, 0.1), (1000.0, 1000.0, 0.001)] {
+            let z = z_for_width(s, n, target).unwrap();
+            assert_abs_diff_eq!(wilson(s, n, z).width(), target, epsilon = tol(1e-9));
+        }
+        assert_eq!(z_for_width(1.0, 2.0, 0.0), None);
+        assert_eq!(z_for_width(1.0, 2.0, 1.0), Some(FP::INFINITY));
+        assert_eq!(z_for_width(0.0, 0.0, 0.5), None);
+
+        let z = z_for_width(500.0, 1000.0, 0.062).unwrap();
+        let confidence = confidence_for_width(500.0, 1000.0, 0.062).unwrap();
+        assert_abs_diff_eq!(z_from_confidence(confidence), z, epsilon = tol(1e-9));
+        assert_abs_diff_eq!(confidence, 0.95, epsilon = 0.001);
+        assert_abs_diff_eq!(confidence_from_z(Z_95_EXACT), 0.95, epsilon = tol(1e-12));
+    }
+
+    #[test]
+    fn confidence_required() {
+        for (s, n, t) in [(40.0, 100.0, 0.3), (3.0, 3.0, 0.2), (1.0, 50.0, 0.001), (700.0, 1000.0, 0.69)] {
+            let c = wilson_confidence_required(s, n, t);
+            assert!(c > 0.0 && c < 1.0);
+            assert_abs_diff_eq!(wilson_lower_bound(s, n, z_from_confidence(c)), t, epsilon = tol(1e-6));
+        }
+        assert_eq!(wilson_confidence_required(30.0, 100.0, 0.3), 0.0);
+        assert_eq!(wilson_confidence_required(20.0, 100.0, 0.3), 0.0);
+        assert_eq!(wilson_confidence_required(20.0, 100.0, -0.1), 1.0);
+        assert_eq!(wilson_confidence_required(20.0, 100.0, 0.0), 1.0);
+        assert_eq!(wilson_confidence_required(0.0, 100.0, 0.0), 0.0);
+        assert_eq!(wilson_confidence_required(0.0, 0.0, 0.1), 0.0);
+        assert!(wilson_confidence_required(80.0, 200.0, 0.3) > wilson_confidence_required(40.0, 100.0, 0.3));
+    }
+
+    #[test]
+    fn invert_bounds() {
+        for (s, n, z) in [(30.0, 100.0, 1.96), (1.0, 2.0, 1.0), (0.0, 10.0, 2.0), (7.0, 7.0, 1.645), (2.5, 40.0, 3.0)] {
+            let out = wilson(s, n, z);
+            assert_abs_diff_eq!(wilson_invert_lower(wilson_lower_bound(s, n, z), n, z), s / n, epsilon = tol(1e-9));
+            if s < n {
+                assert_abs_diff_eq!(wilson_invert_upper(out.high, n, z), s / n, epsilon = tol(1e-9));
+            }
+        }
+        // The upper bound is flat at the top, so it rounds to exactly 1 for proportions within about √ε of 1
+        assert_abs_diff_eq!(wilson_invert_upper(1.0, 7.0, 1.645), 1.0, epsilon = 10.0 * FP::EPSILON.sqrt());
+        // No number of successes out of 10 gets the lower bound above 0.8
+        assert!(wilson_invert_lower(0.8, 10.0, 1.96).is_nan());
+        assert!(wilson_invert_upper(0.1, 10.0, 1.96).is_nan());
+        assert!(wilson_invert_lower(-0.1, 10.0, 1.96).is_nan());
+        assert_eq!(wilson_invert_lower(0.0, 10.0, 1.96), 0.0);
+        assert_eq!(wilson_invert_lower(0.0, 0.0, 1.96), 0.0);
+        assert!(wilson_invert_lower(0.2, 0.0, 1.96).is_nan());
+    }
+
+    #[test]
+    fn one_sided() {
+        assert_abs_diff_eq!(one_sided_confidence_to_z(0.95), 1.6448536269514722, epsilon = tol(1e-9));
+        assert_abs_diff_eq!(one_sided_confidence_to_z(0.975), Z_95_EXACT, epsilon = tol(1e-9));
+        assert_abs_diff_eq!(one_sided_confidence_to_z(0.5), 0.0, epsilon = tol(1e-12));
+        assert!(one_sided_confidence_to_z(0.05) < 0.0);
+        assert!(one_sided_confidence_to_z(1.5).is_nan());
+    }
+
+    #[test]
+    fn information() {
+        let nothing = wilson(0.0, 0.0, 2.0);
+        assert_abs_diff_eq!(nothing.wilson_information(), 0.0, epsilon = tol(1e-6));
+        let narrow = WilsonResult { low: 0.4999, high: 0.5001 };
+        assert!(narrow.wilson_information() > 12.0);
+        let half = WilsonResult { low: 0.25, high: 0.75 };
+        assert_abs_diff_eq!(half.wilson_information(), 1.0, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(wilson_relative_information(&nothing, &half), 1.0, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(wilson_relative_information(&half, &nothing), -1.0, epsilon = tol(1e-6));
+    }
+
+    #[test]
+    fn parts() {
+        for (s, n, z) in [(1.0, 2.0, 2.0), (0.0, 1.0, 1.96), (3.0, 3.0, 0.7), (0.0, 0.0, 2.0), (0.005, 0.01, 2.0)] {
+            let out = wilson(s, n, z);
+            let parts = wilson_parts(s, n, z).to_result();
+            assert_abs_diff_eq!(parts.low, out.low, epsilon = tol(0.0));
+            assert_abs_diff_eq!(parts.high, out.high, epsilon = tol(0.0));
+        }
+        let parts = wilson_parts(10.0, 20.0, 2.0);
+        assert_abs_diff_eq!(parts.center, 0.5, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(parts.half_width, 0.2041241452, epsilon = tol(1e-6));
+    }
+
+    #[test]
+    fn diagnostic() {
+        for (s, n, z) in [(1.0, 2.0, 2.0), (3.0, 10.0, 1.96), (40.0, 50.0, 0.7), (0.5, 3.0, 1.0)] {
+            let out = wilson_diagnostic(s, n, z);
+            assert_eq!(out.result, wilson(s, n, z));
+            assert_eq!((out.s, out.n), (s, n));
+            // Equal up to rounding, as `wilson` avoids the cancellation in the subtraction
+            assert_abs_diff_eq!(out.p_adjusted + out.half_width, out.result.high, epsilon = tol(0.0));
+            assert_abs_diff_eq!(out.p_adjusted - out.half_width, out.result.low, epsilon = tol(0.0));
+        }
+        let out = wilson_diagnostic(0.0, 0.0, 2.0);
+        assert_eq!((out.p_adjusted, out.half_width), (0.5, 0.5));
+        assert_eq!(out.result, wilson(0.0, 0.0, 2.0));
+
+        let text = wilson_diagnostic(10.0, 20.0, 0.0).to_string();
+        assert_eq!(text, "n           20\ns           10\np_adjusted  0.5\nhalf_width  0\nlow         0.5\nhigh        0.5\n");
+    }
+
+    #[test]
+    fn mode() {
+        for z in [0.5, 1.0, 1.96, 3.0] {
+            for n in [2.0, 10.0, 1000.0] {
+                assert_abs_diff_eq!(wilson_mode(0.5 * n, n, z), 0.5, epsilon = tol(1e-12));
+            }
+        }
+        for n in 1..60 {
+            let n = n as FP;
+            for z in [1.0, 1.2, 1.645, 1.96, 2.576, 5.0] {
+                for s in [0.0, 1.0, (0.3 * n).floor(), n - 1.0, n] {
+                    let mode = wilson_mode(s, n, z);
+                    let out = wilson(s, n, z);
+                    // A mode at the boundary is an end of the interval up to rounding
+                    let slack = tol(1e-12);
+                    assert!(out.low - slack <= mode && mode <= out.high + slack, "s = {}, n = {}, z = {}", s, n, z);
+                }
+            }
+        }
+        assert_eq!(wilson_mode(0.0, 5.0, 1.0), 0.0);
+        assert_eq!(wilson_mode(5.0, 5.0, 1.0), 1.0);
+        assert_eq!(wilson_mode(0.0, 0.0, 1.96), 0.5);
+        assert_eq!(wilson_mode(0.0, 0.5, 0.5), 0.5);
+        assert!(wilson_mode(0.0, 5.0, 2.0) > 0.0);
+        assert!(wilson_mode(1.0, 4.0, 0.3) < wilson(1.0, 4.0, 0.3).low);
+    }
+
+    #[cfg(feature = "f32")]
+    #[test]
+    fn single_precision_contains_estimate() {
+        // Without widening these give a lower bound of 3e-8 and an upper bound of 0.99999994
+        assert_eq!(wilson(0.0, 1.0, 2.576).low, 0.0);
+        assert_eq!(wilson(2.0, 2.0, 2.576).high, 1.0);
+        for n in 1..300 {
+            let n = n as FP;
+            for z in [0.5, 1.0, 1.645, 1.96, 2.576, 5.0] {
+                for s in [0.0, 1.0, (0.5 * n).floor(), n - 1.0, n] {
+                    let out = wilson(s, n, z);
+                    assert!(out.low >= 0.0 && out.high <= 1.0);
+                    assert!(out.low <= s / n && s / n <= out.high);
+                }
+            }
+        }
+        assert_eq!(wilson(0.0, 0.0, 1.96), WilsonResult { low: 0.0, high: 1.0 });
+    }
+
+    #[cfg(feature = "f64")]
+    #[test]
+    fn parts_rounding() {
+        // Here rounding makes `center - half_width` slightly negative
+        let parts = wilson_parts(0.0, 1.0, 1.96);
+        let out = parts.to_result();
+        assert!(parts.center - parts.half_width < 0.0);
+        assert_eq!(out.low, 0.0);
+        assert_ne!(0.5 * out.width(), parts.half_width);
+    }
+
+    #[test]
+    fn asymmetry() {
+        for n in [2.0, 10.0, 1000.0] {
+            let out = wilson(0.5 * n, n, 1.96);
+            assert_abs_diff_eq!(wilson_asymmetry(&out, 0.5), 0.0, epsilon = tol(1e-12));
+            assert_abs_diff_eq!(wilson_asymmetry_ratio(&out, 0.5), 0.0, epsilon = tol(1e-12));
+        }
+        let rare = wilson(1.0, 1000.0, 1.96);
+        assert!(wilson_asymmetry(&rare, 0.001) > 0.0);
+        assert!(wilson_asymmetry_ratio(&rare, 0.001) > 0.5);
+        assert_eq!(wilson_asymmetry_ratio(&wilson(0.0, 1000.0, 1.96), 0.0), 1.0);
+        assert!(wilson_asymmetry_ratio(&wilson(999.0, 1000.0, 1.96), 0.999) < -0.5);
+        // More data makes the interval more symmetric
+        assert!(wilson_asymmetry_ratio(&wilson(100.0, 100000.0, 1.96), 0.001) < wilson_asymmetry_ratio(&rare, 0.001));
+        assert_eq!(wilson_asymmetry_ratio(&wilson(3.0, 10.0, 0.0), 0.3), 0.0);
+    }
+
+    #[test]
+    fn star_rating() {
+        assert_eq!(wilson_rating(&[0; 5], 1.96), WilsonResult { low: 0.0, high: 1.0 });
+        assert_eq!(wilson_rating(&[3, 0, 0, 0, 7], 1.96), wilson(7.0, 10.0, 1.96));
+        assert_eq!(wilson_rating(&[1, 2, 3, 4, 5], 2.0), wilson(0.0 + 0.5 + 1.5 + 3.0 + 5.0, 15.0, 2.0));
+
+        let two_perfect = rating_lower_bound(&[0, 0, 0, 0, 2], 1.96);
+        let many_good = rating_lower_bound(&[0, 0, 0, 90, 0], 1.96);
+        assert!(two_perfect < many_good);
+        assert_eq!(many_good, wilson_lower_bound(67.5, 90.0, 1.96));
+    }
+
+    #[test]
+    fn from_outcomes() {
+        assert_eq!(wilson_from_outcomes(&[], 2.0), WilsonResult { low: 0.0, high: 1.0 });
+        let outcomes: Vec<bool> = (0..100).map(|i| i % 7 == 0).collect();
+        let mut counter = WilsonCounter::new();
+        for &outcome in &outcomes {
+            counter.record(outcome);
+        }
+        assert_eq!(wilson_from_outcomes(&outcomes, 2.0), counter.interval(2.0));
+        assert_eq!(wilson_from_outcomes(&outcomes, 2.0), wilson(15.0, 100.0, 2.0));
+        assert_eq!(wilson_from_outcomes(&[true; 3], 1.0), wilson(3.0, 3.0, 1.0));
+    }
+
+    #[test]
+    fn lower_bound_with_prior() {
+        for (s, n) in [(0.0, 0.0), (1.0, 2.0), (10.0, 20.0)] {
+            assert_eq!(wilson_lower_bound_prior(s, n, 1.645, 0.3, 0.0), wilson_lower_bound(s, n, 1.645));
+        }
+        assert_eq!(wilson_lower_bound(1.0, 2.0, 2.0), wilson(1.0, 2.0, 2.0).low);
+
+        let fresh = wilson_lower_bound_prior(0.0, 0.0, 1.645, 0.3, 1000.0);
+        assert_abs_diff_eq!(fresh, 0.3, epsilon = 0.03);
+        assert_eq!(wilson_lower_bound(0.0, 0.0, 1.645), 0.0);
+
+        // Lots of real data outweighs the prior
+        let seasoned = wilson_lower_bound_prior(90000.0, 100000.0, 1.645, 0.3, 10.0);
+        assert_abs_diff_eq!(seasoned, 0.9, epsilon = 0.003);
+    }
+
+    #[test]
+    fn smoothed() {
+        for (s, n, z) in [(0.0, 0.0, 1.96), (3.0, 10.0, 2.0), (0.1, 0.3, 1.0), (1e6, 2e6, 3.0)] {
+            let out = wilson_smoothed(s, n, z, 0.0, 0.0);
+            let plain = wilson(s, n, z);
+            assert_eq!(out.low.to_bits(), plain.low.to_bits());
+            assert_eq!(out.high.to_bits(), plain.high.to_bits());
+        }
+
+        let fresh = wilson_smoothed(0.0, 0.0, 1.96, 20.0, 1000.0);
+        assert!(fresh.contains(&0.02));
+        assert_abs_diff_eq!(fresh.midpoint(), 0.02, epsilon = 0.002);
+        assert!(fresh.width() < 0.02);
+
+        let seasoned = wilson_smoothed(30000.0, 100000.0, 1.96, 20.0, 1000.0);
+        let plain = wilson(30000.0, 100000.0, 1.96);
+        assert_abs_diff_eq!(seasoned.midpoint(), plain.midpoint(), epsilon = 0.003);
+        assert!(seasoned.contains(&0.298));
+    }
+
+    #[test]
+    #[should_panic(expected = "prior successes")]
+    fn smoothed_invalid_prior() {
+        let _ = wilson_smoothed(1.0, 2.0, 1.96, 5.0, 4.0);
+    }
+
+    #[test]
+    fn decay_weights() {
+        let obs = [(3.0, 10.0, 1.0), (5.0, 10.0, 1.0), (1.0, 8.0, 1.0)];
+        assert_eq!(wilson_score_decay(&obs, 2.0), wilson(9.0, 28.0, 2.0));
+
+        let with_ignored = [(3.0, 10.0, 1.0), (50.0, 50.0, 0.0), (5.0, 10.0, 1.0)];
+        assert_eq!(wilson_score_decay(&with_ignored, 2.0), wilson(8.0, 20.0, 2.0));
+
+        let decayed = [(8.0, 10.0, 1.0), (0.0, 10.0, 0.5)];
+        assert_eq!(wilson_score_decay(&decayed, 2.0), wilson(8.0, 15.0, 2.0));
+        assert_eq!(wilson_score_decay(&[], 2.0), wilson(0.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn misclassification() {
+        // With a detector missing half of the cases the whole corrected interval is above the raw one
+        let raw = wilson(300.0, 1000.0, 1.96);
+        let out = wilson_corrected(300.0, 1000.0, 0.5, 1.0, 1.96).unwrap();
+        assert!(raw.high < out.low);
+        assert_abs_diff_eq!(out.low, 2.0 * raw.low, epsilon = tol(1e-12));
+
+        // Observed rate is below the 5% false positive rate, so the lower bound gets clamped
+        let raw = wilson(2.0, 100.0, 1.96);
+        let out = wilson_corrected(2.0, 100.0, 0.9, 0.95, 1.96).unwrap();
+        assert_eq!(out.low, 0.0);
+        assert_abs_diff_eq!(out.high, (raw.high - 0.05) / 0.85, epsilon = tol(1e-12));
+        let out = wilson_corrected(99.0, 100.0, 0.9, 0.95, 1.96).unwrap();
+        assert_eq!(out.high, 1.0);
+
+        assert_eq!(wilson_corrected(3.0, 10.0, 1.0, 1.0, 1.96), Ok(wilson(3.0, 10.0, 1.96)));
+        assert_eq!(wilson_corrected(3.0, 10.0, 0.5, 0.5, 1.96), Err(WilsonError::UninformativeTest));
+        assert_eq!(wilson_corrected(3.0, 10.0, 0.3, 0.6, 1.96), Err(WilsonError::UninformativeTest));
+        assert_eq!(wilson_corrected(3.0, 10.0, 1.2, 0.6, 1.96), Err(WilsonError::OutOfRange));
+        assert_eq!(wilson_corrected(3.0, 10.0, FP::NAN, 0.6, 1.96), Err(WilsonError::NonFinite));
+        assert_eq!(wilson_corrected(11.0, 10.0, 0.9, 0.9, 1.96), Err(WilsonError::SuccessesExceedTrials));
+    }
+
+    #[test]
+    fn migration() {
+        for n in [0.0, 1.0, 3.0, 10.0, 1000.0] {
+            for s in [0.0, 1.0, 0.5 * n, n] {
+                for z in [0.0, 1.0, 1.96, 3.0] {
+                    assert!(!wilson_score_changed(s, n, z, z, 0.0));
+                    let (old, new) = wilson_score_migration_map(s, n, z, z);
+                    assert_eq!(old, new);
+                }
+            }
+        }
+        let (old, new) = wilson_score_migration_map(30.0, 100.0, 1.96, 2.576);
+        assert_eq!(old, wilson(30.0, 100.0, 1.96).low);
+        assert_eq!(new, wilson(30.0, 100.0, 2.576).low);
+        assert!(wilson_score_changed(30.0, 100.0, 1.96, 2.576, 0.01));
+        assert!(!wilson_score_changed(30.0, 100.0, 1.96, 2.576, 0.1));
+    }
+
+    #[test]
+    fn extreme_z() {
+        assert_eq!(wilson(2.0, 20.0, FP::MAX.sqrt() * 2.0), WilsonResult { low: 0.0, high: 1.0 });
+        assert_eq!(wilson(2.0, 20.0, FP::MAX), WilsonResult { low: 0.0, high: 1.0 });
+        assert_eq!(try_wilson(2.0, 20.0, FP::MAX.sqrt() * 2.0), Err(WilsonError::InvalidZ));
+        assert_eq!(try_wilson(2.0, 20.0, -1.0), Err(WilsonError::InvalidZ));
+
+        let huge = wilson(2.0, 20.0, FP::MAX.sqrt() / 2.0);
+        assert_abs_diff_eq!(huge.low, 0.0, epsilon = tol(1e-6));
+        assert_abs_diff_eq!(huge.high, 1.0, epsilon = tol(1e-6));
+
+        let subnormal = FP::MIN_POSITIVE / 4.0;
+        let out = wilson(2.0, 20.0, subnormal);
+        assert_abs_diff_eq!(out.low, 0.1, epsilon = tol(1e-15));
+        assert_abs_diff_eq!(out.high, 0.1, epsilon = tol(1e-15));
+        assert_eq!(try_wilson(2.0, 20.0, subnormal), Ok(out));
     }
 }
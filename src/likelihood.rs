@@ -0,0 +1,121 @@
+//! Profile-likelihood interval, another cross-check for the Wilson interval.
+
+use crate::{z_from_confidence, WilsonResult, FP};
+
+/// Profile-likelihood (likelihood-ratio) interval for `successes` out of `trials`:
+/// all rates `p` with `2 · (ℓ(p̂) − ℓ(p)) ≤ χ²₁(confidence)`, where `ℓ` is the binomial log-likelihood
+/// and `p̂ = successes / trials` its maximum.
+///
+/// Each bound is found by bisection on its side of `p̂`, where the log-likelihood is monotone.
+/// With no successes (or no failures) the maximum is at 0 (or 1), which is then the lower (or upper) bound.
+/// Close to [`wilson`](crate::wilson) for moderate `trials`, differences show mostly with few trials or near 0 and 1.
+///
+/// Zero `trials` result in the `[0, 1]` interval.
+/// Returns NaN bounds for negative or non-finite counts and for successes exceeding trials.
+///
+/// ```
+/// let out = wilson::profile_likelihood_interval(3.0, 10.0, 0.95);
+/// assert!((out.low - 0.08456).abs() < 1e-5 && (out.high - 0.60654).abs() < 1e-5);
+/// ```
+#[must_use]
+pub fn profile_likelihood_interval(successes: FP, trials: FP, confidence: FP) -> WilsonResult {
+    let (s, n) = (successes, trials);
+    if !(s.is_finite() && n.is_finite() && s >= 0.0 && s <= n) {
+        return WilsonResult {
+            low: FP::NAN,
+            high: FP::NAN,
+        };
+    }
+    if n == 0.0 {
+        return WilsonResult { low: 0.0, high: 1.0 };
+    }
+    let z = z_from_confidence(confidence);
+    let critical = z * z;
+    let estimate = s / n;
+    let failures = n - s;
+    // `2 · (ℓ(p̂) − ℓ(p))`, with terms of zero counts skipped so that `0 · ln 0` does not turn into NaN
+    let deviance = |p: FP| {
+        let mut d = 0.0;
+        if s > 0.0 {
+            d += s * (estimate / p).ln();
+        }
+        if failures > 0.0 {
+            d += failures * ((1.0 - estimate) / (1.0 - p)).ln();
+        }
+        2.0 * d
+    };
+    // Bisection between a rate inside the interval and one outside
+    let solve = |mut inside: FP, mut outside: FP| {
+        for _ in 0..200 {
+            let mid = 0.5 * (inside + outside);
+            if mid == inside || mid == outside {
+                break;
+            }
+            if deviance(mid) <= critical {
+                inside = mid;
+            } else {
+                outside = mid;
+            }
+        }
+        inside
+    };
+    let low = if s == 0.0 { 0.0 } else { solve(estimate, 0.0) };
+    let high = if failures == 0.0 { 1.0 } else { solve(estimate, 1.0) };
+    WilsonResult {
+        low: low.clamp(0.0, 1.0),
+        high: high.clamp(0.0, 1.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::{tol, wilson_confidence};
+
+    #[test]
+    fn golden_values() {
+        // Solved with 30-digit arithmetic in mpmath
+        let cases = [
+            (81.0, 263.0, 0.25423898877546, 0.365453341299325),
+            (15.0, 148.0, 0.0596306921009158, 0.156733913925489),
+            (0.0, 20.0, 0.0, 0.0915691154797906),
+            (1.0, 29.0, 0.00200017371116824, 0.143186297225327),
+            (10.0, 10.0, 0.82524667195017, 1.0),
+            (3.0, 10.0, 0.0845586549815098, 0.606539030904485),
+        ];
+        for (s, n, low, high) in cases {
+            let out = profile_likelihood_interval(s, n, 0.95);
+            assert_abs_diff_eq!(out.low, low, epsilon = tol(1e-9) * 100.0);
+            assert_abs_diff_eq!(out.high, high, epsilon = tol(1e-9) * 100.0);
+        }
+        // Without successes the upper bound has the closed form `1 − exp(−χ² / 2n)`
+        let z = z_from_confidence(0.99);
+        let out = profile_likelihood_interval(0.0, 50.0, 0.99);
+        assert_abs_diff_eq!(out.high, 1.0 - (-z * z / 100.0).exp(), epsilon = tol(1e-9));
+    }
+
+    #[test]
+    fn agrees_with_wilson() {
+        for (s, n) in [(30.0, 100.0), (81.0, 263.0), (500.0, 1000.0), (120.0, 400.0), (45.5, 150.0)] {
+            for confidence in [0.8, 0.95, 0.99] {
+                let profile = profile_likelihood_interval(s, n, confidence);
+                let score = wilson_confidence(s, n, confidence);
+                // Within a few percent of the width
+                let tolerance = 0.05 * score.width();
+                assert_abs_diff_eq!(profile.low, score.low, epsilon = tolerance);
+                assert_abs_diff_eq!(profile.high, score.high, epsilon = tolerance);
+                assert!(profile.low < s / n && s / n < profile.high);
+            }
+        }
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        assert_eq!(profile_likelihood_interval(0.0, 0.0, 0.95), WilsonResult { low: 0.0, high: 1.0 });
+        assert!(profile_likelihood_interval(3.0, 2.0, 0.95).low.is_nan());
+        assert!(profile_likelihood_interval(-1.0, 2.0, 0.95).high.is_nan());
+        assert!(profile_likelihood_interval(FP::NAN, 2.0, 0.95).low.is_nan());
+    }
+}
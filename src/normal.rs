@@ -0,0 +1,144 @@
+//! Standard normal distribution helpers used throughout the crate.
+
+use crate::FP;
+
+/// Density of the standard normal distribution.
+pub(crate) fn normal_pdf(x: FP) -> FP {
+    let two_pi: FP = 2.0 * core::f64::consts::PI as FP;
+    (-0.5 * x * x).exp() / two_pi.sqrt()
+}
+
+/// Upper tail `P(Z > x)` of the standard normal distribution.
+///
+/// Uses the Taylor series of `Φ` near zero and Laplace's continued fraction for the Mills ratio
+/// in the tails, so small tail probabilities keep their relative accuracy.
+pub(crate) fn normal_sf(x: FP) -> FP {
+    if x.is_nan() {
+        return FP::NAN;
+    }
+    if x < 0.0 {
+        return 1.0 - normal_sf(-x);
+    }
+    if x < 3.0 {
+        // Φ(x) - 1/2 = φ(x) · (x + x³/3 + x⁵/(3·5) + ...)
+        let mut term = x;
+        let mut sum = x;
+        let mut k: FP = 1.0;
+        while term > sum * FP::EPSILON {
+            k += 2.0;
+            term *= x * x / k;
+            sum += term;
+        }
+        0.5 - normal_pdf(x) * sum
+    } else {
+        // P(Z > x) = φ(x) / (x + 1/(x + 2/(x + 3/(x + ...))))
+        let mut t = x;
+        for k in (1..=200).rev() {
+            t = x + k as FP / t;
+        }
+        normal_pdf(x) / t
+    }
+}
+
+/// Cumulative distribution function `Φ(x)` of the standard normal distribution.
+pub(crate) fn normal_cdf(x: FP) -> FP {
+    normal_sf(-x)
+}
+
+/// Inverse of [`normal_cdf`]. Returns infinities for `p` of `0` or `1` and NaN outside `[0, 1]`.
+pub(crate) fn normal_quantile(p: FP) -> FP {
+    if !(0.0..=1.0).contains(&p) {
+        return FP::NAN;
+    }
+    if p == 0.0 {
+        return FP::NEG_INFINITY;
+    }
+    if p == 1.0 {
+        return FP::INFINITY;
+    }
+    if p > 0.5 {
+        return -normal_quantile(1.0 - p);
+    }
+
+    // Acklam's rational approximation for the lower half, then polished with Halley steps.
+    // Digits beyond `f32` precision are there for `f64`.
+    #[allow(clippy::excessive_precision)]
+    const A: [FP; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    #[allow(clippy::excessive_precision)]
+    const B: [FP; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    #[allow(clippy::excessive_precision)]
+    const C: [FP; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    #[allow(clippy::excessive_precision)]
+    const D: [FP; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    let mut x = if p < 0.02425 {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    };
+    for _ in 0..3 {
+        let e = normal_cdf(x) - p;
+        let u = e / normal_pdf(x);
+        if !u.is_finite() {
+            break;
+        }
+        x -= u / (1.0 + 0.5 * x * u);
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::tol;
+
+    #[test]
+    fn cdf() {
+        assert_abs_diff_eq!(normal_cdf(0.0), 0.5, epsilon = tol(1e-15));
+        assert_abs_diff_eq!(normal_cdf(1.0), 0.8413447460685429, epsilon = tol(1e-14));
+        assert_abs_diff_eq!(normal_cdf(-2.0), 0.022750131948179195, epsilon = tol(1e-15));
+        assert_abs_diff_eq!(normal_sf(3.5) / 2.3262907903552504e-4, 1.0, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(normal_sf(8.0) / 6.22096057427178e-16, 1.0, epsilon = tol(1e-12));
+    }
+
+    #[test]
+    fn quantile() {
+        assert_abs_diff_eq!(normal_quantile(0.975), 1.959963984540054, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(normal_quantile(0.95), 1.6448536269514722, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(normal_quantile(0.5), 0.0, epsilon = tol(1e-15));
+        assert_abs_diff_eq!(normal_quantile(1e-10), -6.361340902404056, epsilon = tol(1e-10));
+        assert!(normal_quantile(1.0).is_infinite());
+        assert!(normal_quantile(1.5).is_nan());
+    }
+}
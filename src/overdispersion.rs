@@ -0,0 +1,216 @@
+//! Intervals for clustered (overdispersed) binary data.
+
+use crate::{wilson, wilson_confidence, z_from_confidence, WilsonResult, FP};
+
+/// Result of [`beta_binomial_interval`].
+#[derive(Debug, PartialEq)]
+pub struct OverdispersedResult {
+    /// Interval for the pooled rate, widened for the clustering
+    pub interval: WilsonResult,
+    /// Estimated intra-class correlation, from `0` (groups are just random samples of one rate) to `1`
+    pub rho: FP,
+}
+
+/// Interval for the overall rate when trials come in groups (e.g. per user) whose rates differ more
+/// than binomial noise explains, as under a beta-binomial model.
+///
+/// The intra-class correlation `ρ` is estimated by the method of moments (the one-way ANOVA estimator):
+/// `ρ = (MSB − MSW) / (MSB + (n₀ − 1)·MSW)`, clamped to `[0, 1]`.
+/// Both counts are then divided by the design effect `1 + (m − 1)·ρ`,
+/// where `m = Σnᵢ² / Σnᵢ` is the trial-weighted mean group size,
+/// and [`wilson_confidence`] is applied to the resulting effective counts.
+/// With `ρ = 0` this is exactly the interval of the pooled counts.
+///
+/// Groups without trials are ignored. With fewer than two groups, or with no trials beyond one per group,
+/// there is no way to estimate `ρ` and it is taken as `0`.
+///
+/// ```
+/// // Every user has either almost all or almost no successes
+/// let users = [(19, 20), (1, 20), (20, 20), (0, 20), (18, 20), (2, 20)];
+/// let out = wilson::beta_binomial_interval(&users, 0.95);
+/// assert!(out.rho > 0.7);
+/// assert!(out.interval.width() > 2.0 * wilson::wilson_confidence(60.0, 120.0, 0.95).width());
+/// ```
+#[must_use]
+pub fn beta_binomial_interval(groups: &[(u64, u64)], confidence: FP) -> OverdispersedResult {
+    let groups: Vec<(FP, FP)> = groups
+        .iter()
+        .filter(|&&(_, n)| n > 0)
+        .map(|&(s, n)| (s as FP, n as FP))
+        .collect();
+    let k = groups.len() as FP;
+    let (s_total, n_total) = groups.iter().fold((0.0, 0.0), |(s, n), &(ds, dn)| (s + ds, n + dn));
+    let sum_sq: FP = groups.iter().map(|&(_, n)| n * n).sum();
+
+    let mut rho = 0.0;
+    if k >= 2.0 && n_total > k {
+        let p = s_total / n_total;
+        let between: FP = groups.iter().map(|&(s, n)| n * (s / n - p).powi(2)).sum::<FP>() / (k - 1.0);
+        let within: FP = groups.iter().map(|&(s, n)| s * (1.0 - s / n)).sum::<FP>() / (n_total - k);
+        let n0 = (n_total - sum_sq / n_total) / (k - 1.0);
+        let denominator = between + (n0 - 1.0) * within;
+        if denominator > 0.0 {
+            rho = ((between - within) / denominator).clamp(0.0, 1.0);
+        }
+    }
+    let design_effect = if n_total > 0.0 { 1.0 + (sum_sq / n_total - 1.0) * rho } else { 1.0 };
+    OverdispersedResult {
+        interval: wilson_confidence(s_total / design_effect, n_total / design_effect, confidence),
+        rho,
+    }
+}
+
+/// Score interval with the binomial variance `p(1 − p) / n` inflated by the dispersion factor `φ`.
+///
+/// `φ` is the ratio of the actual variance of the observed rate to the binomial one.
+/// For trials in clusters of size `m` with intra-class correlation `ρ` (beta-binomial) it is the design effect
+/// `1 + (m − 1)·ρ`, see [`beta_binomial_interval`] for estimating it from grouped data.
+/// Solving the score equation with the inflated variance is the same as using `z·√φ`,
+/// or equivalently [`wilson`] on counts divided by `φ`, so the interval is about `√φ` times wider.
+///
+/// `φ = 1` is exactly [`wilson`], values below 1 (underdispersion) narrow the interval. `φ` must not be negative.
+///
+/// ```
+/// let plain = wilson::wilson(300.0, 1000.0, 1.96);
+/// let clustered = wilson::wilson_overdispersed(300.0, 1000.0, 4.0, 1.96);
+/// assert!((clustered.width() / plain.width() - 2.0).abs() < 0.01);
+/// ```
+#[must_use]
+pub fn wilson_overdispersed(successes: FP, trials: FP, dispersion: FP, z: FP) -> WilsonResult {
+    wilson(successes, trials, z * dispersion.sqrt())
+}
+
+/// Interval for a success rate (e.g. classifier accuracy) measured on `k` cross-validation folds of
+/// `(successes, trials)`, accounting for the variability between folds rather than only pooling the counts.
+///
+/// The dispersion `φ = max(1, X² / (k − 1))` is estimated from Pearson's `X² = Σ nᵢ·(pᵢ − p)² / (p·(1 − p))`
+/// of the fold rates `pᵢ` around the pooled rate `p` (quasi-binomial pooling), and the pooled counts go
+/// through [`wilson_overdispersed`] at the two-sided `confidence` level. Folds agreeing no more than
+/// binomial noise allows give exactly the pooled interval; disagreeing folds widen it by about `√φ`.
+/// This is deliberately quasi-binomial scaling of the pooled variance, not random-effects pooling
+/// of the fold rates: the center stays the pooled rate. "Pooled interval" means `wilson_confidence(Σsᵢ, Σnᵢ)`,
+/// as there is no separate pooling function.
+///
+/// Folds with zero `trials` carry no information and are skipped, even if they report successes.
+/// With fewer than two folds with trials the dispersion cannot be estimated and the pooled interval is returned.
+///
+/// ```
+/// let folds = [(90.0, 100.0), (70.0, 100.0), (88.0, 100.0), (72.0, 100.0), (80.0, 100.0)];
+/// let out = wilson::wilson_kfold(&folds, 0.95);
+/// assert!(out.width() > 1.5 * wilson::wilson_confidence(400.0, 500.0, 0.95).width());
+/// ```
+#[must_use]
+pub fn wilson_kfold(folds: &[(FP, FP)], confidence: FP) -> WilsonResult {
+    let with_trials: Vec<(FP, FP)> = folds.iter().copied().filter(|&(_, n)| n > 0.0).collect();
+    let (s_total, n_total) = with_trials.iter().fold((0.0, 0.0), |(s, n), &(ds, dn)| (s + ds, n + dn));
+    let k = with_trials.len() as FP;
+    let p = s_total / n_total;
+    let variance = p * (1.0 - p);
+    let mut dispersion = 1.0;
+    if k >= 2.0 && variance > 0.0 {
+        let pearson: FP = with_trials.iter().map(|&(s, n)| n * (s / n - p).powi(2)).sum::<FP>() / variance;
+        dispersion = (pearson / (k - 1.0)).max(1.0);
+    }
+    wilson_overdispersed(s_total, n_total, dispersion, z_from_confidence(confidence))
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::bootstrap::SplitMix64;
+    use crate::tol;
+
+    #[test]
+    fn no_overdispersion() {
+        let groups = [(10, 100), (20, 200), (5, 50), (0, 0)];
+        let out = beta_binomial_interval(&groups, 0.95);
+        assert_eq!(out.rho, 0.0);
+        assert_eq!(out.interval, wilson_confidence(35.0, 350.0, 0.95));
+
+        assert_eq!(beta_binomial_interval(&[(3, 10)], 0.9).interval, wilson_confidence(3.0, 10.0, 0.9));
+        assert_eq!(beta_binomial_interval(&[(0, 10), (0, 20)], 0.9).rho, 0.0);
+        let empty = beta_binomial_interval(&[], 0.9);
+        assert_eq!((empty.interval, empty.rho), (wilson_confidence(0.0, 0.0, 0.9), 0.0));
+    }
+
+    #[test]
+    fn dispersion() {
+        for (s, n, z) in [(0.0, 10.0, 1.96), (3.0, 10.0, 2.0), (500.0, 1000.0, 1.0), (0.0, 0.0, 1.96)] {
+            assert_eq!(wilson_overdispersed(s, n, 1.0, z), wilson(s, n, z));
+        }
+        let plain = wilson(3000.0, 10000.0, 1.96);
+        for phi in [1.5, 2.0, 4.0, 9.0] {
+            let out = wilson_overdispersed(3000.0, 10000.0, phi, 1.96);
+            assert!(out.low < plain.low && out.high > plain.high);
+            assert!((out.width() / plain.width() / phi.sqrt() - 1.0).abs() < 0.002);
+            // Same as shrinking the counts by the dispersion
+            let shrunk = wilson(3000.0 / phi, 10000.0 / phi, 1.96);
+            assert!((out.low - shrunk.low).abs() < 1e-6 && (out.high - shrunk.high).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn coverage() {
+        let mut rng = SplitMix64(7);
+        let mut uniform = move || (rng.next() >> 11) as FP / (1u64 << 53) as FP;
+        // Rates of groups follow Beta(2, 8): mean 0.2 and ρ = 1 / (2 + 8 + 1)
+        let (true_rate, true_rho) = (0.2, 1.0 / 11.0);
+        let replications = 200;
+        let (mut naive_hits, mut adjusted_hits, mut rho_sum) = (0, 0, 0.0);
+        for _ in 0..replications {
+            let groups: Vec<(u64, u64)> = (0..30)
+                .map(|_| {
+                    // The 2nd smallest of 9 uniforms is Beta(2, 8) distributed
+                    let mut u: Vec<FP> = (0..9).map(|_| uniform()).collect();
+                    u.sort_by(|a, b| a.total_cmp(b));
+                    let rate = u[1];
+                    ((0..50).filter(|_| uniform() < rate).count() as u64, 50)
+                })
+                .collect();
+            let (s, n) = groups.iter().fold((0, 0), |(s, n), &(ds, dn)| (s + ds, n + dn));
+            let out = beta_binomial_interval(&groups, 0.95);
+            let naive = wilson_confidence(s as FP, n as FP, 0.95);
+            naive_hits += usize::from(naive.low <= true_rate && true_rate <= naive.high);
+            adjusted_hits += usize::from(out.interval.low <= true_rate && true_rate <= out.interval.high);
+            rho_sum += out.rho;
+        }
+        let naive_coverage = naive_hits as FP / replications as FP;
+        let adjusted_coverage = adjusted_hits as FP / replications as FP;
+        // Close to the nominal 95%, short of it as ρ itself is estimated from the data
+        assert!(naive_coverage < 0.75);
+        assert!(adjusted_coverage > 0.85);
+        assert!(adjusted_coverage > naive_coverage + 0.2);
+        assert!((rho_sum / replications as FP - true_rho).abs() < 0.02);
+    }
+
+    #[test]
+    fn kfold() {
+        let identical = [(80.0, 100.0); 5];
+        let pooled = wilson_confidence(400.0, 500.0, 0.95);
+        let out = wilson_kfold(&identical, 0.95);
+        assert_abs_diff_eq!(out.low, pooled.low, epsilon = tol(1e-12));
+        assert_abs_diff_eq!(out.high, pooled.high, epsilon = tol(1e-12));
+
+        // Binomial-sized differences are not penalized
+        let noisy = [(78.0, 100.0), (82.0, 100.0), (80.0, 100.0), (81.0, 100.0), (79.0, 100.0)];
+        assert_eq!(wilson_kfold(&noisy, 0.95), wilson_overdispersed(400.0, 500.0, 1.0, z_from_confidence(0.95)));
+
+        let dispersed = [(95.0, 100.0), (65.0, 100.0), (90.0, 100.0), (70.0, 100.0), (80.0, 100.0)];
+        let out = wilson_kfold(&dispersed, 0.95);
+        assert!(out.low < pooled.low && out.high > pooled.high);
+        // X² = 100 · 0.065 / 0.16 = 40.625 over 4 degrees of freedom
+        let expected = wilson_overdispersed(400.0, 500.0, 40.625 / 4.0, z_from_confidence(0.95));
+        assert_abs_diff_eq!(out.low, expected.low, epsilon = tol(1e-9));
+        assert_abs_diff_eq!(out.high, expected.high, epsilon = tol(1e-9));
+
+        assert_eq!(wilson_kfold(&[(3.0, 10.0)], 0.9), wilson_confidence(3.0, 10.0, 0.9));
+        assert_eq!(wilson_kfold(&[(3.0, 10.0), (0.0, 0.0)], 0.9), wilson_confidence(3.0, 10.0, 0.9));
+        let mut with_empty = dispersed.to_vec();
+        with_empty.insert(2, (5.0, 0.0));
+        assert_eq!(wilson_kfold(&with_empty, 0.95), out);
+        assert_eq!(wilson_kfold(&[(3.0, 10.0), (2.0, 0.0)], 0.9), wilson_confidence(3.0, 10.0, 0.9));
+        assert_eq!(wilson_kfold(&[], 0.9), WilsonResult { low: 0.0, high: 1.0 });
+    }
+}
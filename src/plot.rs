@@ -0,0 +1,176 @@
+//! Drawing intervals as error bars with `plotters`.
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use crate::{WilsonResult, FP};
+
+/// Error returned by [`draw_intervals`], coming from the drawing backend.
+pub type DrawError<E> = DrawingAreaErrorKind<E>;
+
+/// Appearance of the error bars drawn by [`draw_intervals`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntervalStyle {
+    /// Colour of the horizontal line from `low` to `high` and of its caps
+    pub whisker_color: RGBColor,
+    /// Colour of the tick at the middle of the interval
+    pub center_color: RGBColor,
+    /// Colour of the labels and of the axis
+    pub label_color: RGBColor,
+    /// Line width in pixels
+    pub stroke_width: u32,
+    /// Half height of the vertical caps at both ends of the whisker in pixels, `0` to draw no caps.
+    /// Caps get shorter when rows are too dense for them.
+    pub cap_half_height: u32,
+    /// Label font size in pixels
+    pub font_size: f64,
+}
+
+impl Default for IntervalStyle {
+    fn default() -> Self {
+        IntervalStyle {
+            whisker_color: RGBColor(31, 119, 180),
+            center_color: RGBColor(214, 39, 40),
+            label_color: RGBColor(0, 0, 0),
+            stroke_width: 1,
+            cap_half_height: 4,
+            font_size: 12.0,
+        }
+    }
+}
+
+/// Cut `label` with an ellipsis until it is no wider than `max_width` pixels.
+fn fit_label<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    label: &str,
+    font: &TextStyle,
+    max_width: u32,
+) -> Result<Option<String>, DrawError<DB::ErrorType>> {
+    if area.estimate_text_size(label, font)?.0 <= max_width {
+        return Ok(Some(label.to_owned()));
+    }
+    let mut chars: Vec<char> = label.chars().collect();
+    while chars.pop().is_some() {
+        let candidate: String = chars.iter().chain(['…'].iter()).collect();
+        if area.estimate_text_size(&candidate, font)?.0 <= max_width {
+            return Ok(Some(candidate));
+        }
+    }
+    Ok(None)
+}
+
+/// Draw labeled horizontal error bars, one row per item, on a `0..=1` horizontal axis filling `area`.
+///
+/// Labels take up to a third of the width and are truncated with an ellipsis when longer.
+/// Each interval is a whisker from `low` to `high` with a tick at its [`midpoint`](WilsonResult::midpoint).
+/// When there are more items than fit vertically, rows are squeezed (possibly several per pixel)
+/// and labels are left out, so the drawing still shows the overall picture.
+/// Bounds are clamped to `[0, 1]`; intervals with NaN bounds are skipped.
+///
+/// The crate enables no `plotters` font features: drawing labels on bitmap backends
+/// needs `plotters` with `ttf` or `ab_glyph` enabled in the application.
+pub fn draw_intervals<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    items: &[(String, WilsonResult)],
+    style: IntervalStyle,
+) -> Result<(), DrawError<DB::ErrorType>> {
+    let (width, height) = area.dim_in_pixel();
+    let (width, height) = (width as i32, height as i32);
+    let margin = style.font_size.ceil() as i32 / 2 + 2;
+    let font = ("sans-serif", style.font_size).into_font().color(&style.label_color);
+    let axis_height = style.font_size.ceil() as i32 + 6;
+
+    let label_width = if items.is_empty() { 0 } else { width / 3 };
+    let x0 = label_width + margin;
+    let x1 = (width - margin).max(x0 + 1);
+    let top = margin;
+    let bottom = (height - axis_height).max(top + 1);
+    let to_x = |v: FP| x0 + ((x1 - x0) as FP * v.clamp(0.0, 1.0)).round() as i32;
+
+    let axis = style.label_color.stroke_width(1);
+    area.draw(&PathElement::new(vec![(x0, bottom), (x1, bottom)], axis))?;
+    for (i, text) in ["0", "0.25", "0.5", "0.75", "1"].iter().enumerate() {
+        let x = to_x(i as FP / 4.0);
+        area.draw(&PathElement::new(vec![(x, bottom), (x, bottom + 3)], axis))?;
+        let tick_font = font.pos(Pos::new(HPos::Center, VPos::Top));
+        area.draw_text(text, &tick_font, (x, bottom + 4))?;
+    }
+
+    if items.is_empty() {
+        return Ok(());
+    }
+    let rows = items.len() as i64;
+    let rows_height = i64::from(bottom - top);
+    let pitch = rows_height as f64 / rows as f64;
+    let show_labels = pitch >= f64::from(area.estimate_text_size("Ag", &font)?.1);
+    let cap = (style.cap_half_height as i32).min((pitch / 2.0).floor() as i32);
+    let label_font = font.pos(Pos::new(HPos::Right, VPos::Center));
+    let whisker = style.whisker_color.stroke_width(style.stroke_width);
+    let center = style.center_color.stroke_width(style.stroke_width);
+
+    for (i, (label, result)) in items.iter().enumerate() {
+        if result.low.is_nan() || result.high.is_nan() {
+            continue;
+        }
+        let y = top + ((2 * i as i64 + 1) * rows_height / (2 * rows)) as i32;
+        if show_labels {
+            if let Some(text) = fit_label(area, label, &font, (label_width - margin).max(0) as u32)? {
+                area.draw_text(&text, &label_font, (label_width, y))?;
+            }
+        }
+        let (low, high) = (to_x(result.low), to_x(result.high));
+        area.draw(&PathElement::new(vec![(low, y), (high, y)], whisker))?;
+        if cap > 0 {
+            for x in [low, high] {
+                area.draw(&PathElement::new(vec![(x, y - cap), (x, y + cap)], whisker))?;
+            }
+        }
+        let mid = to_x(result.midpoint());
+        let tick = cap.max(1);
+        area.draw(&PathElement::new(vec![(mid, y - tick), (mid, y + tick)], center))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wilson;
+
+    fn render(items: &[(String, WilsonResult)], size: (u32, u32)) -> String {
+        let mut svg = String::new();
+        {
+            let area = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+            area.fill(&WHITE).unwrap();
+            draw_intervals(&area, items, IntervalStyle::default()).unwrap();
+            area.present().unwrap();
+        }
+        svg
+    }
+
+    #[test]
+    fn draws_any_number_of_items() {
+        let svg = render(&[], (400, 300));
+        assert!(svg.contains("\n0.5\n"));
+
+        let svg = render(&[("control".to_owned(), wilson(20.0, 100.0, 1.96))], (400, 300));
+        assert!(svg.contains("\ncontrol\n"));
+        assert_eq!(svg.matches("<polyline").count(), 1 + 5 + 4);
+
+        let items: Vec<(String, WilsonResult)> = (0..500)
+            .map(|i| (format!("variant {}", i), wilson(i as FP, 500.0, 1.96)))
+            .collect();
+        let svg = render(&items, (400, 300));
+        assert!(!svg.contains("variant"));
+        assert_eq!(svg.matches("<polyline").count(), 1 + 5 + 500 * 2);
+    }
+
+    #[test]
+    fn truncates_long_labels() {
+        let long = "a very long label that cannot possibly fit into a third of the drawing".to_owned();
+        let svg = render(&[(long, wilson(3.0, 10.0, 1.96))], (300, 100));
+        assert!(svg.contains("…\n</text>"));
+        assert!(!svg.contains("drawing"));
+    }
+}
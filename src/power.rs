@@ -0,0 +1,230 @@
+//! Power and sample size planning for comparing two proportions, and interval widths to expect.
+
+use crate::normal::{normal_cdf, normal_quantile};
+use crate::{wilson, WilsonError, FP};
+
+fn check_proportion(p: FP) -> Result<(), WilsonError> {
+    if !p.is_finite() {
+        return Err(WilsonError::NonFinite);
+    }
+    if p <= 0.0 || p >= 1.0 {
+        return Err(WilsonError::OutOfRange);
+    }
+    Ok(())
+}
+
+/// Pooled null and unpooled alternative standard deviations (per one trial in each arm).
+fn deviations(p1: FP, p2: FP) -> (FP, FP) {
+    let pooled = 0.5 * (p1 + p2);
+    let null = (2.0 * pooled * (1.0 - pooled)).sqrt();
+    let alternative = (p1 * (1.0 - p1) + p2 * (1.0 - p2)).sqrt();
+    (null, alternative)
+}
+
+/// Probability to detect a difference between true rates `p1` and `p2` with `n_per_arm` trials in each arm.
+///
+/// `z_alpha` is the significance threshold of the test, e.g. `1.96` for a two-sided test at 5% level.
+/// Uses the usual normal approximation with pooled variance under the null hypothesis.
+///
+/// Rates must be strictly between 0 and 1.
+///
+/// ```
+/// let power = wilson::power_two_proportions(0.05, 0.06, 8200.0, 1.96).unwrap();
+/// assert!(power > 0.8 && power < 0.81);
+/// ```
+pub fn power_two_proportions(p1: FP, p2: FP, n_per_arm: FP, z_alpha: FP) -> Result<FP, WilsonError> {
+    check_proportion(p1)?;
+    check_proportion(p2)?;
+    if !n_per_arm.is_finite() || !z_alpha.is_finite() {
+        return Err(WilsonError::NonFinite);
+    }
+    if n_per_arm < 0.0 {
+        return Err(WilsonError::Negative);
+    }
+    let (null, alternative) = deviations(p1, p2);
+    let z_beta = ((p1 - p2).abs() * n_per_arm.sqrt() - z_alpha * null) / alternative;
+    Ok(normal_cdf(z_beta))
+}
+
+/// Number of trials per arm needed to detect a difference between true rates `p1` and `p2`
+/// with the given `power` (e.g. `0.8`) at threshold `z_alpha`.
+///
+/// Inverse of [`power_two_proportions`]. Returns infinity when `p1 == p2`, as no finite sample suffices.
+/// The result is not rounded; round it up to get a whole number of trials.
+///
+/// ```
+/// let n = wilson::required_n_two_proportions(0.05, 0.06, 0.8, 1.96).unwrap();
+/// assert_eq!(n.ceil(), 8158.0);
+/// ```
+pub fn required_n_two_proportions(p1: FP, p2: FP, power: FP, z_alpha: FP) -> Result<FP, WilsonError> {
+    check_proportion(p1)?;
+    check_proportion(p2)?;
+    check_proportion(power)?;
+    if !z_alpha.is_finite() {
+        return Err(WilsonError::NonFinite);
+    }
+    if p1 == p2 {
+        return Ok(FP::INFINITY);
+    }
+    let (null, alternative) = deviations(p1, p2);
+    let z_beta = normal_quantile(power);
+    let numerator = z_alpha * null + z_beta * alternative;
+    Ok(numerator * numerator / ((p1 - p2) * (p1 - p2)))
+}
+
+/// Width of the Wilson interval to expect from `trials` trials if the true rate is about `expected_p`,
+/// for planning before collecting data: `wilson(expected_p · trials, trials, z).width()`.
+///
+/// This is the width at the expected number of successes, not the average width over possible outcomes,
+/// which is a little smaller for small `trials`.
+///
+/// ```
+/// let width = wilson::wilson_expected_width(0.1, 1000.0, 1.96);
+/// assert!(width > 0.037 && width < 0.038);
+/// ```
+#[must_use]
+pub fn wilson_expected_width(expected_p: FP, trials: FP, z: FP) -> FP {
+    wilson(expected_p * trials, trials, z).width()
+}
+
+/// Largest [`wilson_expected_width`] for any true rate, reached at `0.5`.
+///
+/// Use when nothing is known about the rate in advance.
+#[must_use]
+pub fn wilson_expected_width_worst_case(trials: FP, z: FP) -> FP {
+    wilson_expected_width(0.5, trials, z)
+}
+
+/// `(trials, worst-case width)` for each of `n_values`, see [`wilson_expected_width_worst_case`].
+///
+/// ```
+/// let table = wilson::wilson_width_table(1.96, &[100.0, 400.0, 1600.0]);
+/// let widths: Vec<_> = table.iter().map(|&(_, width)| (1000.0 * width).round()).collect();
+/// // About ±9.6, ±4.9 and ±2.4 percentage points: four times the trials halve the width
+/// assert_eq!(widths, [192.0, 98.0, 49.0]);
+/// assert_eq!(table[1].0, 400.0);
+/// ```
+#[must_use]
+pub fn wilson_width_table(z: FP, n_values: &[FP]) -> Vec<(FP, FP)> {
+    n_values
+        .iter()
+        .map(|&n| (n, wilson_expected_width_worst_case(n, z)))
+        .collect()
+}
+
+/// Number of trials for which the Wilson interval has half-width `target_half_width`
+/// if the true rate is `assumed_p`, the inverse of [`wilson_expected_width`] (which is twice the half-width).
+///
+/// Solves `z / (n + z²) · sqrt(n·p·(1 − p) + z²/4) = target_half_width` exactly, which is a quadratic equation in `n`,
+/// rather than using the normal approximation `n = z²·p·(1 − p) / half_width²`, which is too small near 0 and 1.
+/// The result is not rounded; round it up to get a whole number of trials.
+/// Returns 0 for half-widths of ½ and more (already reached without data) and infinity for non-positive ones.
+///
+/// ```
+/// let n = wilson::wilson_precision_to_trials(0.02, 0.1, 1.96);
+/// assert_eq!(n.ceil(), 868.0);
+/// ```
+#[must_use]
+pub fn wilson_precision_to_trials(target_half_width: FP, assumed_p: FP, z: FP) -> FP {
+    let h = target_half_width;
+    if h.is_nan() || h <= 0.0 {
+        return FP::INFINITY;
+    }
+    if h >= 0.5 {
+        return 0.0;
+    }
+    let h2 = h * h;
+    let z2 = z * z;
+    let a = h2;
+    let b = z2 * (2.0 * h2 - assumed_p * (1.0 - assumed_p));
+    let c = z2 * z2 * (h2 - 0.25);
+    // `c < 0`, so there is exactly one positive root
+    ((-b + (b * b - 4.0 * a * c).sqrt()) / (2.0 * a)).max(0.0)
+}
+
+/// [`wilson_precision_to_trials`] for the worst case `assumed_p = 0.5`, enough whatever the true rate is.
+#[must_use]
+pub fn wilson_precision_to_trials_conservative(target_half_width: FP, z: FP) -> FP {
+    wilson_precision_to_trials(target_half_width, 0.5, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::tol;
+
+    #[test]
+    fn textbook_sample_size() {
+        let n = required_n_two_proportions(0.05, 0.06, 0.8, 1.96).unwrap();
+        assert_abs_diff_eq!(n, 8157.6, epsilon = 1.0);
+        let n = required_n_two_proportions(0.5, 0.6, 0.9, 1.96).unwrap();
+        assert_abs_diff_eq!(n, 518.05, epsilon = 0.01);
+        assert_eq!(required_n_two_proportions(0.3, 0.3, 0.8, 1.96), Ok(FP::INFINITY));
+    }
+
+    #[test]
+    fn power_round_trip() {
+        let n = required_n_two_proportions(0.05, 0.06, 0.8, 1.96).unwrap();
+        let power = power_two_proportions(0.05, 0.06, n, 1.96).unwrap();
+        assert_abs_diff_eq!(power, 0.8, epsilon = tol(1e-6));
+        let power = power_two_proportions(0.06, 0.05, n, 1.96).unwrap();
+        assert_abs_diff_eq!(power, 0.8, epsilon = tol(1e-6));
+        assert!(power_two_proportions(0.05, 0.06, 2.0 * n, 1.96).unwrap() > 0.95);
+    }
+
+    #[test]
+    fn expected_width() {
+        let width = wilson_expected_width(0.3, 100.0, 1.96);
+        assert_abs_diff_eq!(width, wilson(30.0, 100.0, 1.96).width(), epsilon = tol(1e-12));
+        // About `2z · sqrt(p·(1 − p) / n)` for many trials
+        assert_abs_diff_eq!(wilson_expected_width_worst_case(10000.0, 1.96), 0.0196, epsilon = 1e-5);
+        for p in [0.0, 0.01, 0.2, 0.45, 0.55, 0.9, 1.0] {
+            assert!(wilson_expected_width(p, 50.0, 1.96) < wilson_expected_width_worst_case(50.0, 1.96));
+        }
+
+        let n_values = [1.0, 2.0, 5.0, 10.0, 30.0, 100.0, 1000.0, 1e5];
+        let table = wilson_width_table(1.96, &n_values);
+        assert_eq!(table.len(), n_values.len());
+        for (row, &n) in table.iter().zip(&n_values) {
+            assert_eq!(row.0, n);
+            assert_eq!(row.1, wilson_expected_width_worst_case(n, 1.96));
+        }
+        for pair in table.windows(2) {
+            assert!(pair[1].1 < pair[0].1);
+        }
+        assert!(wilson_width_table(1.96, &[]).is_empty());
+    }
+
+    #[test]
+    fn precision_round_trip() {
+        for p in [0.0, 0.01, 0.1, 0.3, 0.5, 0.8, 1.0] {
+            for h in [0.005, 0.02, 0.1, 0.3, 0.45] {
+                for z in [1.0, 1.96, 3.0] {
+                    let n = wilson_precision_to_trials(h, p, z);
+                    let width = wilson_expected_width(p, n, z);
+                    assert_abs_diff_eq!(width, 2.0 * h, epsilon = tol(1e-9) * 100.0);
+                }
+            }
+        }
+        // Close to the normal approximation for rates away from 0 and 1
+        let n = wilson_precision_to_trials_conservative(0.01, 1.96);
+        assert_abs_diff_eq!(n, 1.96 * 1.96 * 0.25 / 0.0001, epsilon = 10.0);
+        assert_eq!(n, wilson_precision_to_trials(0.01, 0.5, 1.96));
+        assert!(wilson_precision_to_trials(0.01, 0.2, 1.96) < n);
+
+        assert_eq!(wilson_precision_to_trials(0.5, 0.3, 1.96), 0.0);
+        assert_eq!(wilson_precision_to_trials(0.0, 0.3, 1.96), FP::INFINITY);
+        assert_eq!(wilson_precision_to_trials(FP::NAN, 0.3, 1.96), FP::INFINITY);
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        assert_eq!(power_two_proportions(0.0, 0.5, 100.0, 1.96), Err(WilsonError::OutOfRange));
+        assert_eq!(power_two_proportions(0.5, 1.0, 100.0, 1.96), Err(WilsonError::OutOfRange));
+        assert_eq!(power_two_proportions(0.5, 0.6, -1.0, 1.96), Err(WilsonError::Negative));
+        assert_eq!(required_n_two_proportions(0.5, 0.6, 1.0, 1.96), Err(WilsonError::OutOfRange));
+        assert_eq!(required_n_two_proportions(FP::NAN, 0.6, 0.8, 1.96), Err(WilsonError::NonFinite));
+    }
+}
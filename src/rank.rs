@@ -0,0 +1,90 @@
+//! Stability of rankings by the Wilson lower bound.
+
+use crate::{wilson, FP};
+
+/// Evenly spaced `z` values from `z_range.0` to `z_range.1`, both included.
+fn z_steps(z_range: (FP, FP), steps: usize) -> impl Iterator<Item = FP> {
+    let (z_min, z_max) = z_range;
+    let last = steps.saturating_sub(1).max(1) as FP;
+    (0..steps).map(move |i| z_min + (z_max - z_min) * i as FP / last)
+}
+
+/// Indices of `items` sorted by descending lower bound at `z`; ties keep the original order.
+fn ranking(items: &[(FP, FP)], z: FP) -> Vec<usize> {
+    let lows: Vec<FP> = items.iter().map(|&(s, n)| wilson(s, n, z).low).collect();
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| lows[b].total_cmp(&lows[a]));
+    order
+}
+
+/// Ranking of `(successes, trials)` items by descending Wilson lower bound at each of `steps` evenly spaced
+/// `z` values from `z_range.0` to `z_range.1` (both included), as permutations of item indices, best first.
+///
+/// Items whose positions change between rows are the ones the ranking is not sure about.
+/// Ties keep the original order. `steps = 1` uses only `z_range.0`.
+///
+/// ```
+/// let items = [(90.0, 100.0), (5.0, 5.0), (40.0, 100.0)];
+/// let rankings = wilson::wilson_rank_stability(&items, (0.5, 3.0), 3);
+/// assert_eq!(rankings[0], [1, 0, 2]); // At low confidence a perfect record of 5 wins
+/// assert_eq!(rankings[2], [0, 1, 2]);
+/// ```
+#[must_use]
+pub fn wilson_rank_stability(items: &[(FP, FP)], z_range: (FP, FP), steps: usize) -> Vec<Vec<usize>> {
+    z_steps(z_range, steps).map(|z| ranking(items, z)).collect()
+}
+
+/// Fraction of the rankings of [`wilson_rank_stability`] identical to the one at `z_range.0`,
+/// from `1 / steps` (changes at every step) to 1 (completely stable).
+///
+/// Zero `steps` result in 1, as there is nothing to be unstable.
+#[must_use]
+pub fn wilson_rank_stability_score(items: &[(FP, FP)], z_range: (FP, FP), steps: usize) -> FP {
+    let rankings = wilson_rank_stability(items, z_range, steps);
+    let Some(first) = rankings.first() else {
+        return 1.0;
+    };
+    let unchanged = rankings.iter().filter(|r| *r == first).count();
+    unchanged as FP / steps as FP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dominant_item_stays_first() {
+        let items = [(30.0, 100.0), (950.0, 1000.0), (4.0, 10.0), (50.0, 200.0)];
+        let rankings = wilson_rank_stability(&items, (0.0, 4.0), 41);
+        assert_eq!(rankings.len(), 41);
+        for ranking in &rankings {
+            assert_eq!(ranking[0], 1);
+            let mut sorted = ranking.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, [0, 1, 2, 3]);
+        }
+        // The small item drops behind the better-measured ones as confidence grows
+        assert_eq!(rankings[0], [1, 2, 0, 3]);
+        assert_eq!(rankings[40], [1, 0, 3, 2]);
+        let score = wilson_rank_stability_score(&items, (0.0, 4.0), 41);
+        assert!(score > 0.0 && score < 1.0);
+    }
+
+    #[test]
+    fn stable_and_degenerate() {
+        let items = [(900.0, 1000.0), (500.0, 1000.0), (100.0, 1000.0)];
+        assert_eq!(wilson_rank_stability_score(&items, (1.0, 3.0), 10), 1.0);
+        assert_eq!(wilson_rank_stability(&items, (1.0, 3.0), 1), [[0, 1, 2]]);
+        assert!(wilson_rank_stability(&items, (1.0, 3.0), 0).is_empty());
+        assert_eq!(wilson_rank_stability_score(&items, (1.0, 3.0), 0), 1.0);
+        assert_eq!(wilson_rank_stability(&[], (1.0, 3.0), 2), [Vec::<usize>::new(), Vec::new()]);
+
+        // Two items swapping halfway
+        let items = [(9.0, 10.0), (80.0, 100.0)];
+        let rankings = wilson_rank_stability(&items, (0.0, 2.0), 5);
+        assert_eq!(rankings.first().unwrap(), &[0, 1]);
+        assert_eq!(rankings.last().unwrap(), &[1, 0]);
+        let unchanged = rankings.iter().filter(|r| r[0] == 0).count();
+        assert_eq!(wilson_rank_stability_score(&items, (0.0, 2.0), 5), unchanged as FP / 5.0);
+    }
+}
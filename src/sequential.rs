@@ -0,0 +1,185 @@
+//! Group-sequential boundaries for repeatedly peeking at an experiment.
+
+use crate::normal::{normal_pdf, normal_quantile, normal_sf};
+use crate::FP;
+
+/// Alpha spending function used by [`sequential_boundaries`].
+///
+/// Both are Lan–DeMets approximations of the classic designs, applied to each side with `alpha / 2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spending {
+    /// Approximately constant boundaries: `a(t) = a · ln(1 + (e − 1)·t)`.
+    Pocock,
+    /// Very strict early boundaries relaxing towards the end: `a(t) = 2 − 2Φ(z_{1−a/2} / √t)`.
+    OBrienFleming,
+}
+
+impl Spending {
+    /// Amount of one-sided `alpha` spent by information fraction `t`.
+    fn spent(self, alpha: FP, t: FP) -> FP {
+        match self {
+            Spending::Pocock => alpha * (1.0 + ((1.0 as FP).exp() - 1.0) * t).ln(),
+            Spending::OBrienFleming => 2.0 * normal_sf(normal_quantile(1.0 - alpha / 2.0) / t.sqrt()),
+        }
+    }
+}
+
+/// Number of Simpson's rule intervals used to integrate over the continuation region.
+const GRID_INTERVALS: usize = 800;
+
+/// Beyond this many standard deviations the continuation density is treated as zero.
+const MAX_Z: FP = 12.0;
+
+/// Calculate two-sided z thresholds for each interim look of a group-sequential experiment.
+///
+/// `looks` are information fractions (share of the planned trials observed so far) for each look.
+/// The returned thresholds keep the overall two-sided type I error at `alpha`,
+/// distributing it between looks according to `spending`.
+/// They can be passed as `z` to [`wilson`](crate::wilson) at the corresponding look.
+///
+/// Boundaries are found by numerically integrating the joint distribution
+/// of the test statistics across looks (Armitage–McPherson–Rowe recursion).
+///
+/// Panics if `looks` is empty, not strictly increasing, not within `(0, 1]`,
+/// or if `alpha` is not within `(0, 1)`.
+///
+/// ```
+/// use wilson::{sequential_boundaries, Spending};
+/// let z = sequential_boundaries(&[0.25, 0.5, 0.75, 1.0], 0.05, Spending::OBrienFleming);
+/// assert!(z[0] > 4.0);
+/// assert!(z[3] < 2.1);
+/// ```
+#[must_use]
+pub fn sequential_boundaries(looks: &[FP], alpha: FP, spending: Spending) -> Vec<FP> {
+    assert!(!looks.is_empty(), "at least one look is required");
+    assert!(alpha > 0.0 && alpha < 1.0, "alpha must be within (0, 1)");
+    assert!(looks[0] > 0.0, "information fractions must be positive");
+    assert!(
+        looks.windows(2).all(|w| w[0] < w[1]),
+        "information fractions must be strictly increasing",
+    );
+    assert!(looks[looks.len() - 1] <= 1.0, "information fractions must not exceed 1");
+
+    let one_side = alpha / 2.0;
+    let mut boundaries = Vec::with_capacity(looks.len());
+
+    // Continuation region grid on the Brownian motion scale and its density times Simpson weights.
+    let mut grid: Vec<FP> = Vec::new();
+    let mut weighted: Vec<FP> = Vec::new();
+    let mut prev_t = 0.0;
+    let mut prev_spent = 0.0;
+
+    for &t in looks {
+        let spent = spending.spent(one_side, t);
+        let target = spent - prev_spent;
+        prev_spent = spent;
+        let sd = (t - prev_t).sqrt();
+        prev_t = t;
+
+        let first = boundaries.is_empty();
+        let crossing = |c: FP| -> FP {
+            let b = c * t.sqrt();
+            if first {
+                normal_sf(b / sd)
+            } else {
+                grid.iter()
+                    .zip(&weighted)
+                    .map(|(&u, &w)| w * normal_sf((b - u) / sd))
+                    .sum()
+            }
+        };
+
+        let c = if target <= crossing(MAX_Z) {
+            FP::INFINITY
+        } else {
+            let mut lo: FP = 0.0;
+            let mut hi: FP = MAX_Z;
+            for _ in 0..60 {
+                let mid = 0.5 * (lo + hi);
+                if crossing(mid) > target {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            0.5 * (lo + hi)
+        };
+        boundaries.push(c);
+
+        let b = c.min(MAX_Z) * t.sqrt();
+        let h = 2.0 * b / GRID_INTERVALS as FP;
+        let new_grid: Vec<FP> = (0..=GRID_INTERVALS).map(|j| -b + h * j as FP).collect();
+        let new_weighted = new_grid
+            .iter()
+            .enumerate()
+            .map(|(j, &s)| {
+                let density = if first {
+                    normal_pdf(s / sd) / sd
+                } else {
+                    grid.iter()
+                        .zip(&weighted)
+                        .map(|(&u, &w)| w * normal_pdf((s - u) / sd))
+                        .sum::<FP>()
+                        / sd
+                };
+                let simpson = if j == 0 || j == GRID_INTERVALS {
+                    1.0
+                } else if j % 2 == 1 {
+                    4.0
+                } else {
+                    2.0
+                };
+                density * simpson * h / 3.0
+            })
+            .collect();
+        grid = new_grid;
+        weighted = new_weighted;
+    }
+    boundaries
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+
+    #[test]
+    fn single_look() {
+        for spending in [Spending::Pocock, Spending::OBrienFleming] {
+            let z = sequential_boundaries(&[1.0], 0.05, spending);
+            assert_eq!(z.len(), 1);
+            assert_abs_diff_eq!(z[0], 1.959964, epsilon = 0.00001);
+        }
+    }
+
+    #[test]
+    fn obrien_fleming_four_looks() {
+        let z = sequential_boundaries(&[0.25, 0.5, 0.75, 1.0], 0.05, Spending::OBrienFleming);
+        let expected = [4.33, 2.96, 2.36, 2.01];
+        for (got, want) in z.iter().zip(expected) {
+            assert_abs_diff_eq!(*got, want, epsilon = 0.005);
+        }
+    }
+
+    #[test]
+    fn pocock_four_looks() {
+        let z = sequential_boundaries(&[0.25, 0.5, 0.75, 1.0], 0.05, Spending::Pocock);
+        for w in z.windows(2) {
+            assert_abs_diff_eq!(w[0], w[1], epsilon = 0.1);
+        }
+        assert!(z.iter().all(|&x| x > 2.2 && x < 2.5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn non_increasing_looks() {
+        let _ = sequential_boundaries(&[0.5, 0.5, 1.0], 0.05, Spending::Pocock);
+    }
+
+    #[test]
+    #[should_panic]
+    fn look_past_end() {
+        let _ = sequential_boundaries(&[0.5, 1.2], 0.05, Spending::Pocock);
+    }
+}
@@ -0,0 +1,139 @@
+//! Running intervals over asynchronous streams of outcomes.
+
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::{WilsonCounter, WilsonResult, FP};
+
+/// Stream returned by [`WilsonStreamExt::running_wilson`] and [`WilsonStreamExt::running_wilson_every`].
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct RunningWilson<S> {
+    inner: S,
+    counter: WilsonCounter,
+    z: FP,
+    every: u64,
+}
+
+impl<S: Stream<Item = bool> + Unpin> Stream for RunningWilson<S> {
+    type Item = WilsonResult;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<WilsonResult>> {
+        let this = &mut *self;
+        loop {
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(outcome)) => {
+                    this.counter.record(outcome);
+                    if this.counter.trials().is_multiple_of(this.every) {
+                        return Poll::Ready(Some(this.counter.interval(this.z)));
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Outcomes counted since the last output count towards the next one
+        let every = self.every as usize;
+        let pending = (self.counter.trials() % self.every) as usize;
+        let (low, high) = self.inner.size_hint();
+        let outputs = |items: usize| (items + pending) / every;
+        (outputs(low), high.map(outputs))
+    }
+}
+
+/// Running Wilson intervals over a [`Stream`] of outcomes, without collecting them first.
+///
+/// The adapters poll the inner stream directly (no spawning or channels) and end when it ends.
+/// They need an [`Unpin`] stream; pin other streams with `Box::pin` first.
+///
+/// ```
+/// use futures::stream::{self, StreamExt};
+/// use wilson::WilsonStreamExt;
+///
+/// let outcomes = stream::iter([true, false, false, true]);
+/// let intervals: Vec<_> = futures::executor::block_on(outcomes.running_wilson(1.96).collect());
+/// assert_eq!(intervals.len(), 4);
+/// assert_eq!(intervals[3], wilson::wilson(2.0, 4.0, 1.96));
+/// ```
+pub trait WilsonStreamExt: Stream<Item = bool> + Sized {
+    /// Emit the interval of all outcomes so far after each outcome.
+    fn running_wilson(self, z: FP) -> RunningWilson<Self> {
+        self.running_wilson_every(z, 1)
+    }
+
+    /// Emit the interval of all outcomes so far after every `every`-th outcome.
+    /// Outcomes after the last multiple of `every` are not reported when the inner stream ends.
+    ///
+    /// Panics if `every` is zero.
+    fn running_wilson_every(self, z: FP, every: usize) -> RunningWilson<Self> {
+        assert!(every > 0, "every must be positive");
+        RunningWilson {
+            inner: self,
+            counter: WilsonCounter::new(),
+            z,
+            every: every as u64,
+        }
+    }
+}
+
+impl<S: Stream<Item = bool>> WilsonStreamExt for S {}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream::{self, StreamExt};
+    use futures::task::noop_waker_ref;
+
+    use super::*;
+    use crate::wilson;
+
+    fn poll<S: Stream + Unpin>(stream: &mut S) -> Poll<Option<S::Item>> {
+        Pin::new(stream).poll_next(&mut Context::from_waker(noop_waker_ref()))
+    }
+
+    #[test]
+    fn every_item() {
+        let outcomes = [true, false, false, true, true, false, false];
+        let mut running = stream::iter(outcomes).running_wilson(2.0);
+        assert_eq!(running.size_hint(), (7, Some(7)));
+        let mut successes = 0;
+        for (i, &outcome) in outcomes.iter().enumerate() {
+            successes += usize::from(outcome);
+            let expected = wilson(successes as FP, (i + 1) as FP, 2.0);
+            assert_eq!(poll(&mut running), Poll::Ready(Some(expected)));
+        }
+        assert_eq!(poll(&mut running), Poll::Ready(None));
+    }
+
+    #[test]
+    fn every_k_items() {
+        let outcomes = (0..10).map(|i| i % 3 == 0);
+        let mut running = stream::iter(outcomes).running_wilson_every(1.0, 4);
+        assert_eq!(running.size_hint(), (2, Some(2)));
+        assert_eq!(poll(&mut running), Poll::Ready(Some(wilson(2.0, 4.0, 1.0))));
+        assert_eq!(poll(&mut running), Poll::Ready(Some(wilson(3.0, 8.0, 1.0))));
+        assert_eq!(poll(&mut running), Poll::Ready(None));
+    }
+
+    #[test]
+    fn pending_is_propagated() {
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        let mut running = receiver.running_wilson_every(2.0, 2);
+        assert_eq!(poll(&mut running), Poll::Pending);
+        sender.unbounded_send(true).unwrap();
+        assert_eq!(poll(&mut running), Poll::Pending);
+        sender.unbounded_send(false).unwrap();
+        assert_eq!(poll(&mut running), Poll::Ready(Some(wilson(1.0, 2.0, 2.0))));
+        sender.unbounded_send(true).unwrap();
+        drop(sender);
+        assert_eq!(poll(&mut running), Poll::Ready(None));
+
+        let boxed = Box::pin(stream::iter([true, true]).then(|x| async move { x }));
+        let out: Vec<WilsonResult> = futures::executor::block_on(boxed.running_wilson(2.0).collect());
+        assert_eq!(out, [wilson(1.0, 1.0, 2.0), wilson(2.0, 2.0, 2.0)]);
+    }
+}
@@ -0,0 +1,103 @@
+//! Aggregate statistics over a collection of intervals.
+
+use crate::{WilsonResult, FP};
+
+/// Overview of a set of intervals, see [`interval_summary`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IntervalSummary {
+    /// Number of summarized intervals
+    pub count: usize,
+    /// Average of `high − low`
+    pub mean_width: FP,
+    /// Share of intervals touching `0` or `1` (with a bound clamped to the valid range)
+    pub fraction_clamped: FP,
+    /// Number of intervals containing `0.5`, i.e. not telling on which side of a coin flip the rate is
+    pub straddling_half: usize,
+    /// Smallest lower bound
+    pub min_low: FP,
+    /// Largest lower bound
+    pub max_low: FP,
+}
+
+/// Summarize a whole column of intervals in one pass: mean width, share clamped to `[0, 1]` edges,
+/// number straddling `0.5` and the range of lower bounds.
+///
+/// For an empty slice `count` and `straddling_half` are zero and the other fields are NaN.
+///
+/// ```
+/// use wilson::{interval_summary, WilsonResult};
+/// let summary = interval_summary(&[
+///     WilsonResult { low: 0.0, high: 0.5 },
+///     WilsonResult { low: 0.25, high: 0.75 },
+/// ]);
+/// assert_eq!(summary.mean_width, 0.5);
+/// assert_eq!(summary.straddling_half, 2);
+/// ```
+#[must_use]
+pub fn interval_summary(results: &[WilsonResult]) -> IntervalSummary {
+    if results.is_empty() {
+        return IntervalSummary {
+            count: 0,
+            mean_width: FP::NAN,
+            fraction_clamped: FP::NAN,
+            straddling_half: 0,
+            min_low: FP::NAN,
+            max_low: FP::NAN,
+        };
+    }
+    let mut total_width = 0.0;
+    let mut clamped = 0usize;
+    let mut straddling_half = 0;
+    let mut min_low = FP::INFINITY;
+    let mut max_low = FP::NEG_INFINITY;
+    for r in results {
+        total_width += r.width();
+        if r.low <= 0.0 || r.high >= 1.0 {
+            clamped += 1;
+        }
+        if r.low <= 0.5 && 0.5 <= r.high {
+            straddling_half += 1;
+        }
+        min_low = min_low.min(r.low);
+        max_low = max_low.max(r.low);
+    }
+    let count = results.len();
+    IntervalSummary {
+        count,
+        mean_width: total_width / count as FP,
+        fraction_clamped: clamped as FP / count as FP,
+        straddling_half,
+        min_low,
+        max_low,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::tol;
+
+    #[test]
+    fn hand_built() {
+        let results = [
+            WilsonResult { low: 0.0, high: 0.2 },
+            WilsonResult { low: 0.1, high: 0.6 },
+            WilsonResult { low: 0.5, high: 1.0 },
+            WilsonResult { low: 0.7, high: 0.9 },
+        ];
+        let summary = interval_summary(&results);
+        assert_eq!(summary.count, 4);
+        assert_abs_diff_eq!(summary.mean_width, (0.2 + 0.5 + 0.5 + 0.2) / 4.0, epsilon = tol(1e-12));
+        assert_eq!(summary.fraction_clamped, 0.5);
+        assert_eq!(summary.straddling_half, 2);
+        assert_eq!(summary.min_low, 0.0);
+        assert_eq!(summary.max_low, 0.7);
+
+        let empty = interval_summary(&[]);
+        assert_eq!(empty.count, 0);
+        assert_eq!(empty.straddling_half, 0);
+        assert!(empty.mean_width.is_nan() && empty.min_low.is_nan());
+    }
+}
@@ -0,0 +1,295 @@
+//! Precomputed tables of Wilson bounds for small integer counts, built at compile time.
+
+use crate::FP;
+
+/// Lookup table of Wilson bounds for all `successes <= trials <= max_trials`.
+///
+/// Created at compile time by the [`wilson_table!`](crate::wilson_table) macro.
+/// Entries are either [`FP`] bounds or, with the `per_mille` option, `u16` bounds in thousandths
+/// (lower bounds rounded down and upper bounds rounded up, so the interval never gets narrower).
+#[derive(Debug)]
+pub struct WilsonTable<T: 'static = FP> {
+    max_trials: usize,
+    lower: &'static [T],
+    upper: Option<&'static [T]>,
+}
+
+impl<T: Copy> WilsonTable<T> {
+    #[doc(hidden)]
+    pub const fn from_parts(max_trials: usize, lower: &'static [T], upper: Option<&'static [T]>) -> Self {
+        WilsonTable {
+            max_trials,
+            lower,
+            upper,
+        }
+    }
+
+    /// Largest number of trials covered by the table.
+    #[must_use]
+    pub fn max_trials(&self) -> usize {
+        self.max_trials
+    }
+
+    fn index(&self, successes: usize, trials: usize) -> Option<usize> {
+        if trials > self.max_trials || successes > trials {
+            return None;
+        }
+        Some(trials * (trials + 1) / 2 + successes)
+    }
+
+    /// Lower bound for the given counts or `None` if they are outside of the table.
+    #[must_use]
+    pub fn lookup(&self, successes: usize, trials: usize) -> Option<T> {
+        self.index(successes, trials).map(|i| self.lower[i])
+    }
+
+    /// Upper bound for the given counts or `None` if they are outside of the table
+    /// or the table was generated with `lower_only`.
+    #[must_use]
+    pub fn lookup_upper(&self, successes: usize, trials: usize) -> Option<T> {
+        let upper = self.upper?;
+        self.index(successes, trials).map(|i| upper[i])
+    }
+}
+
+/// Generate a [`WilsonTable`] at compile time.
+///
+/// ```
+/// use wilson::{wilson_table, WilsonTable};
+///
+/// static TABLE: WilsonTable = wilson_table!(max_trials = 255, z = 1.96, lower_only);
+/// static COMPACT: WilsonTable<u16> = wilson_table!(max_trials = 32, z = 1.96, per_mille);
+///
+/// assert!((TABLE.lookup(2, 10).unwrap() - wilson::wilson(2.0, 10.0, 1.96).low).abs() < 1e-6);
+/// assert_eq!(TABLE.lookup_upper(2, 10), None);
+/// assert_eq!(TABLE.lookup(2, 300), None);
+/// assert_eq!(COMPACT.lookup(2, 10), Some(56));
+/// assert_eq!(COMPACT.lookup_upper(2, 10), Some(510));
+/// ```
+///
+/// Options after `z` are `lower_only` (do not store upper bounds) and `per_mille` (store `u16` thousandths),
+/// in this order. `z` is an `f64` expression. Invalid parameters fail the compilation:
+///
+/// ```compile_fail
+/// static TABLE: wilson::WilsonTable = wilson::wilson_table!(max_trials = 10, z = -1.0);
+/// ```
+///
+/// ```compile_fail
+/// static TABLE: wilson::WilsonTable = wilson::wilson_table!(max_trials = 0, z = 1.96);
+/// ```
+///
+/// ```compile_fail
+/// static TABLE: wilson::WilsonTable = wilson::wilson_table!(max_trials = 10, z = f64::NAN, lower_only);
+/// ```
+#[macro_export]
+macro_rules! wilson_table {
+    (max_trials = $max:expr, z = $z:expr $(,)?) => {
+        $crate::__wilson_table!($max, $z, $crate::FP, __fill_bounds, upper)
+    };
+    (max_trials = $max:expr, z = $z:expr, lower_only $(,)?) => {
+        $crate::__wilson_table!($max, $z, $crate::FP, __fill_bounds)
+    };
+    (max_trials = $max:expr, z = $z:expr, per_mille $(,)?) => {
+        $crate::__wilson_table!($max, $z, u16, __fill_per_mille, upper)
+    };
+    (max_trials = $max:expr, z = $z:expr, lower_only, per_mille $(,)?) => {
+        $crate::__wilson_table!($max, $z, u16, __fill_per_mille)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __wilson_table {
+    ($max:expr, $z:expr, $t:ty, $fill:ident) => {{
+        const MAX: usize = $max;
+        const LEN: usize = $crate::__table_len(MAX);
+        const LOWER: [$t; LEN] = $crate::$fill::<LEN>(MAX, $z, false);
+        $crate::WilsonTable::from_parts(MAX, &LOWER, None)
+    }};
+    ($max:expr, $z:expr, $t:ty, $fill:ident, upper) => {{
+        const MAX: usize = $max;
+        const LEN: usize = $crate::__table_len(MAX);
+        const LOWER: [$t; LEN] = $crate::$fill::<LEN>(MAX, $z, false);
+        const UPPER: [$t; LEN] = $crate::$fill::<LEN>(MAX, $z, true);
+        $crate::WilsonTable::from_parts(MAX, &LOWER, Some(&UPPER))
+    }};
+}
+
+/// Table backing [`wilson_lower_bound_small`].
+#[cfg(feature = "small-table")]
+static SMALL: WilsonTable = crate::wilson_table!(max_trials = 32, z = 1.96, lower_only);
+
+/// [`wilson_lower_bound`](crate::wilson_lower_bound) at `z = 1.96`, served from a precomputed table
+/// when both counts are at most 32 and computed as usual otherwise.
+///
+/// Avoids the square root in hot ranking paths over small counts.
+#[cfg(feature = "small-table")]
+#[must_use]
+pub fn wilson_lower_bound_small(successes: u8, trials: u8) -> FP {
+    match SMALL.lookup(successes.into(), trials.into()) {
+        Some(low) => low,
+        None => crate::wilson_lower_bound(successes.into(), trials.into(), 1.96),
+    }
+}
+
+/// Square root usable in constant evaluation: bit-trick seed followed by Newton iterations.
+const fn const_sqrt(x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let mut r = f64::from_bits((x.to_bits() >> 1) + (0x3ff << 51));
+    let mut i = 0;
+    while i < 6 {
+        r = 0.5 * (r + x / r);
+        i += 1;
+    }
+    r
+}
+
+/// Same formula as [`wilson`](crate::wilson), returning `(low, high)`.
+const fn const_wilson(s: f64, n: f64, z: f64) -> (f64, f64) {
+    if n <= 0.001 {
+        return (0.0, 1.0);
+    }
+    let p = (s + 0.5 * z * z) / (n + z * z);
+    let d = z / (n + z * z) * const_sqrt(s * (n - s) / n + z * z / 4.0);
+    if z == 0.0 {
+        return (p - d, p + d);
+    }
+    // Cancellation-free form, as in `generic::wilson_bounds`
+    let f = n - s;
+    let scale = n * (n + z * z);
+    let complement = (f + 0.5 * z * z) / (n + z * z);
+    let high = if p + d < 0.5 {
+        p + d
+    } else {
+        1.0 - f * f / scale / (complement + d)
+    };
+    (s * s / scale / (p + d), high)
+}
+
+const fn check_parameters(max_trials: usize, z: f64) {
+    assert!(max_trials >= 1, "max_trials must be at least 1");
+    assert!(max_trials <= 4095, "max_trials is too large for a precomputed table");
+    assert!(z.is_finite() && z >= 0.0, "z must be finite and non-negative");
+}
+
+#[doc(hidden)]
+pub const fn __table_len(max_trials: usize) -> usize {
+    (max_trials + 1) * (max_trials + 2) / 2
+}
+
+#[doc(hidden)]
+pub const fn __fill_bounds<const LEN: usize>(max_trials: usize, z: f64, upper: bool) -> [FP; LEN] {
+    check_parameters(max_trials, z);
+    let mut out = [0.0; LEN];
+    let mut n = 0;
+    let mut i = 0;
+    while n <= max_trials {
+        let mut s = 0;
+        while s <= n {
+            let (low, high) = const_wilson(s as f64, n as f64, z);
+            out[i] = if upper { high } else { low } as FP;
+            i += 1;
+            s += 1;
+        }
+        n += 1;
+    }
+    out
+}
+
+#[doc(hidden)]
+pub const fn __fill_per_mille<const LEN: usize>(max_trials: usize, z: f64, upper: bool) -> [u16; LEN] {
+    check_parameters(max_trials, z);
+    let mut out = [0; LEN];
+    let mut n = 0;
+    let mut i = 0;
+    while n <= max_trials {
+        let mut s = 0;
+        while s <= n {
+            let (low, high) = const_wilson(s as f64, n as f64, z);
+            out[i] = if upper {
+                let x = high * 1000.0;
+                let t = x as u16;
+                if (t as f64) < x && t < 1000 {
+                    t + 1
+                } else {
+                    t
+                }
+            } else {
+                (low * 1000.0) as u16
+            };
+            i += 1;
+            s += 1;
+        }
+        n += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wilson;
+
+    static FULL: WilsonTable = crate::wilson_table!(max_trials = 64, z = 1.96);
+    static LOWER: WilsonTable = crate::wilson_table!(max_trials = 255, z = 2.0, lower_only);
+    static PER_MILLE: WilsonTable<u16> = crate::wilson_table!(max_trials = 64, z = 1.96, per_mille);
+
+    #[test]
+    fn matches_formula() {
+        let tolerance = 8.0 * FP::EPSILON;
+        for n in 0..=64 {
+            for s in 0..=n {
+                let live = wilson(s as FP, n as FP, 1.96);
+                assert!((FULL.lookup(s, n).unwrap() - live.low).abs() <= tolerance);
+                assert!((FULL.lookup_upper(s, n).unwrap() - live.high).abs() <= tolerance);
+
+                let low = PER_MILLE.lookup(s, n).unwrap() as FP / 1000.0;
+                let high = PER_MILLE.lookup_upper(s, n).unwrap() as FP / 1000.0;
+                assert!(low <= live.low + tolerance && live.low - low < 0.001);
+                assert!(high + tolerance >= live.high && high - live.high < 0.001);
+            }
+        }
+        for n in [1, 7, 100, 255] {
+            for s in 0..=n {
+                let live = wilson(s as FP, n as FP, 2.0);
+                assert!((LOWER.lookup(s, n).unwrap() - live.low).abs() <= tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn small_upper_bounds() {
+        // Relative accuracy, which `1 − …` would lose for upper bounds this small
+        for n in [1000, 4095] {
+            for s in 0..=5 {
+                let (_, high) = const_wilson(s as f64, n as f64, 1.96);
+                let live = wilson(s as FP, n as FP, 1.96).high;
+                assert!((high as FP - live).abs() <= 8.0 * FP::EPSILON * live, "{s} of {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn bounds_checking() {
+        assert_eq!(LOWER.max_trials(), 255);
+        assert_eq!(LOWER.lookup(0, 0), Some(0.0));
+        assert_eq!(LOWER.lookup(5, 4), None);
+        assert_eq!(LOWER.lookup(0, 256), None);
+        assert_eq!(LOWER.lookup_upper(1, 2), None);
+        assert_eq!(FULL.lookup_upper(0, 0), Some(1.0));
+        assert_eq!(FULL.lookup_upper(65, 65), None);
+    }
+
+    #[cfg(feature = "small-table")]
+    #[test]
+    fn small_table() {
+        for n in 0..=40u8 {
+            for s in 0..=n {
+                let live = crate::wilson_lower_bound(s.into(), n.into(), 1.96);
+                assert!((wilson_lower_bound_small(s, n) - live).abs() <= 8.0 * FP::EPSILON);
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Golden values guarding against silent drift of computed intervals.
+//!
+//! Intervals are often persisted or used as thresholds, so any change of these values
+//! (constants, algorithm, order of operations) must be deliberate.
+//! Ignored in routine runs; run before releases with `cargo test --test stability -- --ignored`.
+//! If a change is intended, regenerate the table and mention it in the release notes.
+
+#![cfg(feature = "f64")]
+
+/// Allowed difference in units of `f64::EPSILON` relative to the golden value.
+/// Leaves room for the `fma` feature, which rounds differently in the last bits.
+const ULPS: f64 = 4.0;
+
+/// `(successes, trials, z, low, high)` as computed by `wilson` at the time of writing.
+#[rustfmt::skip]
+const GOLDEN: &[(f64, f64, f64, f64, f64)] = &[
+    (0.0, 1.0, 1.96, 0.0, 0.7934567085261071),
+    (1.0, 1.0, 1.96, 0.20654329147389294, 1.0),
+    (1.0, 2.0, 1.96, 0.09452865480086614, 0.9054713451991339),
+    (2.0, 10.0, 1.5, 0.07503541424670111, 0.43516866738595195),
+    (3.0, 10.0, 1.0, 0.17882082075676464, 0.4575428156068717),
+    (0.0, 5.0, 2.576, 0.0, 0.5702907996853841),
+    (5.0, 5.0, 2.576, 0.4297092003146159, 1.0),
+    (1.0, 3.0, 0.5, 0.21478268248769744, 0.47752500981999485),
+    (7.0, 9.0, 3.0, 0.3137583358133438, 0.964019441964434),
+    (10.0, 20.0, 1.96, 0.2992949144298199, 0.7007050855701801),
+    (30.0, 100.0, 1.959963984540054, 0.2189488529493276, 0.39584854633346667),
+    (1.0, 100.0, 1.645, 0.002233840095275507, 0.04358650004543192),
+    (99.0, 100.0, 1.645, 0.9564134999545681, 0.9977661599047245),
+    (0.0, 1000.0, 1.96, 0.0, 0.003826898586390522),
+    (1000.0, 1000.0, 1.96, 0.9961731014136095, 1.0),
+    (1.0, 1000.0, 2.326, 0.00013751339936213502, 0.007232886701859827),
+    (500.0, 1000.0, 1.96, 0.4690690341793595, 0.5309309658206405),
+    (12345.0, 1000000.0, 1.96, 0.012130442258015737, 0.0125633044784868),
+    (999999.0, 1000000.0, 3.29, 0.9999872544970041, 0.9999999215418037),
+    (3.0, 1000000000.0, 1.96, 1.0202522646170292e-9, 8.82134769757548e-9),
+    (450000000.0, 1000000000.0, 2.0, 0.4499685359346172, 0.4500314644653828),
+    (0.5, 2.0, 1.0, 0.06981019498596838, 0.5968564716806983),
+    (2.5, 7.5, 1.96, 0.10968357558501171, 0.6698889714982923),
+    (0.0, 1.0, 0.0, 0.0, 0.0),
+    (4.0, 10.0, 0.0, 0.4, 0.4),
+    (4.0, 10.0, 10.0, 0.015045369879912706, 0.9667728119382691),
+    (4.0, 10.0, 100.0, 0.00015989767469388376, 0.9996403021255059),
+    (0.0, 0.0, 1.96, 0.0, 1.0),
+    (17.0, 40.0, 1.28, 0.3298468487456067, 0.5260553953367787),
+];
+
+fn assert_close(got: f64, want: f64, what: &str, case: &(f64, f64, f64, f64, f64)) {
+    let tolerance = ULPS * f64::EPSILON * want.abs().max(f64::MIN_POSITIVE);
+    assert!(
+        (got - want).abs() <= tolerance,
+        "{} drifted for {:?}: got {:?}, golden {:?}",
+        what,
+        case,
+        got,
+        want,
+    );
+}
+
+#[test]
+#[ignore = "release check, run with --ignored"]
+fn wilson_score_api_stability_test() {
+    for case in GOLDEN {
+        let &(s, n, z, low, high) = case;
+        let out = wilson::wilson(s, n, z);
+        assert_close(out.low, low, "low", case);
+        assert_close(out.high, high, "high", case);
+    }
+}